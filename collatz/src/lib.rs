@@ -0,0 +1,45 @@
+use viewport::{ComputeError, ComputeKernel, ComputeRunner, StorageAccess};
+
+/// Matches the `threads(..)` of the shader's entry point.
+const WORKGROUP_SIZE: u32 = 64;
+
+/// Computes the number of steps the Collatz sequence takes to reach 1, for many numbers at once.
+pub struct Collatz {
+    kernel: ComputeKernel,
+}
+
+impl Collatz {
+    pub fn new(runner: &ComputeRunner) -> Self {
+        let shader = unsafe {
+            runner
+                .device()
+                .create_shader_module_spirv(&wgpu::include_spirv_raw!("../../target/collatz.spv"))
+        };
+        let kernel = ComputeKernel::new(
+            runner.device(),
+            &shader,
+            "main_cs",
+            [WORKGROUP_SIZE, 1, 1],
+            &[StorageAccess::ReadOnly, StorageAccess::ReadWrite],
+        );
+
+        Self { kernel }
+    }
+
+    /// The number of steps for every number, `u32::MAX` where the sequence overflows a `u32`.
+    pub fn steps(&self, runner: &ComputeRunner, numbers: &[u32]) -> Result<Vec<u32>, ComputeError> {
+        // Empty storage buffers can't be bound
+        if numbers.is_empty() {
+            return Ok(vec![]);
+        }
+        let numbers_buffer = runner.create_storage_buffer("Numbers Buffer", numbers);
+        let steps_buffer = runner.create_storage_buffer("Steps Buffer", &vec![0u32; numbers.len()]);
+
+        runner.dispatch(
+            &self.kernel,
+            &[&numbers_buffer, &steps_buffer],
+            [numbers.len() as u32, 1, 1],
+        )?;
+        runner.read_buffer(&steps_buffer)
+    }
+}
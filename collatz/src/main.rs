@@ -0,0 +1,25 @@
+use collatz::Collatz;
+use viewport::ComputeRunner;
+
+const COUNT: u32 = 1 << 20;
+
+pub fn main() {
+    env_logger::init();
+
+    let runner =
+        pollster::block_on(ComputeRunner::new(false)).expect("Error creating compute runner!");
+    let collatz = Collatz::new(&runner);
+
+    let numbers = (1..=COUNT).collect::<Vec<_>>();
+    let steps = collatz
+        .steps(&runner, &numbers)
+        .expect("Error reading back results!");
+
+    let (number, max_steps) = numbers
+        .iter()
+        .zip(steps.iter())
+        .filter(|(_, steps)| **steps != u32::MAX)
+        .max_by_key(|(_, steps)| **steps)
+        .expect("At least one number is given");
+    println!("Up to {COUNT}, {number} takes the most steps to reach 1: {max_steps}");
+}
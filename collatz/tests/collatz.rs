@@ -0,0 +1,39 @@
+use collatz::Collatz;
+use viewport::ComputeRunner;
+
+fn cpu_steps(mut n: u32) -> u32 {
+    let mut steps = 0;
+    while n != 1 {
+        n = if n % 2 == 0 { n / 2 } else { 3 * n + 1 };
+        steps += 1;
+    }
+    steps
+}
+
+#[test]
+fn collatz_matches_cpu() {
+    let runner =
+        pollster::block_on(ComputeRunner::new(true)).expect("Error creating compute runner!");
+    let collatz = Collatz::new(&runner);
+
+    // Not a multiple of the workgroup size, to cover the bounds check in the shader
+    let numbers = (1..=1000).collect::<Vec<_>>();
+    let steps = collatz
+        .steps(&runner, &numbers)
+        .expect("Error reading back results!");
+
+    let expected = numbers.iter().map(|n| cpu_steps(*n)).collect::<Vec<_>>();
+    assert_eq!(steps, expected);
+}
+
+#[test]
+fn collatz_of_nothing_is_empty() {
+    let runner =
+        pollster::block_on(ComputeRunner::new(true)).expect("Error creating compute runner!");
+    let collatz = Collatz::new(&runner);
+
+    let steps = collatz
+        .steps(&runner, &[])
+        .expect("Error reading back results!");
+    assert!(steps.is_empty());
+}
@@ -0,0 +1,263 @@
+//! Golden image tests for viewport dressers.
+//!
+//! Dressers are rendered offscreen on the fallback (software) adapter and compared against
+//! reference PNGs checked in under the crate's `tests/golden` directory. Run the tests with
+//! `UPDATE_GOLDEN=1` to (re)generate the references.
+
+use std::{
+    fmt, fs,
+    path::{Path, PathBuf},
+};
+
+use image::{Rgba, RgbaImage};
+use viewport::{FrameDresser, Viewport};
+
+/// Compare an image against the reference of the calling crate, see [`assert_matches_reference`].
+#[macro_export]
+macro_rules! assert_golden {
+    ($name:expr, $image:expr) => {
+        $crate::assert_golden!($name, $image, $crate::Tolerance::default())
+    };
+    ($name:expr, $image:expr, $tolerance:expr) => {
+        $crate::assert_matches_reference(
+            ::std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/golden"),
+            ::std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("golden"),
+            $name,
+            $image,
+            $tolerance,
+        )
+    };
+}
+
+/// Render a dresser and compare it against the reference of the calling crate, see
+/// [`assert_dresser_matches_reference`].
+#[macro_export]
+macro_rules! assert_dresser_golden {
+    ($name:expr, $size:expr, $new_dresser:expr) => {
+        $crate::assert_dresser_matches_reference(
+            ::std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/golden"),
+            ::std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("golden"),
+            $name,
+            $size,
+            $new_dresser,
+        )
+    };
+}
+
+/// Create a headless viewport on the fallback adapter, so the rendered images don't depend on
+/// the GPU (or lack thereof) of the machine running the tests.
+pub fn headless_viewport(width: u32, height: u32) -> Viewport {
+    pollster::block_on(Viewport::new_headless(width, height, true))
+        .expect("Error creating headless viewport on the fallback adapter!")
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Tolerance {
+    /// Maximum difference of any color channel for a pixel to still count as matching.
+    pub channel: u8,
+    /// Fraction of pixels allowed to exceed the channel tolerance, e.g. along triangle edges.
+    pub differing_pixels: f64,
+}
+
+impl Default for Tolerance {
+    fn default() -> Self {
+        Self {
+            channel: 2,
+            differing_pixels: 0.001,
+        }
+    }
+}
+
+/// Summary of the differences between a rendered image and its reference.
+#[derive(Debug)]
+pub struct Comparison {
+    pub differing_pixels: usize,
+    pub total_pixels: usize,
+    pub max_channel_difference: u8,
+    /// Mean absolute difference over all color channels, in 0-255 units.
+    pub mean_absolute_error: f64,
+    /// Mean difference in perceived brightness (Rec. 709 luma), in 0-255 units.
+    pub mean_luma_error: f64,
+    /// Peak signal-to-noise ratio in dB, infinite for identical images.
+    pub psnr: f64,
+    /// The reference dimmed to grayscale, with pixels exceeding the tolerance marked in red.
+    pub diff: RgbaImage,
+}
+
+impl Comparison {
+    pub fn passes(&self, tolerance: Tolerance) -> bool {
+        self.differing_pixels as f64 <= tolerance.differing_pixels * self.total_pixels as f64
+    }
+}
+
+impl fmt::Display for Comparison {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} of {} pixels ({:.3}%) differ, max channel difference {}, \
+            mean absolute error {:.3}, mean luma error {:.3}, PSNR {:.2} dB",
+            self.differing_pixels,
+            self.total_pixels,
+            100. * self.differing_pixels as f64 / self.total_pixels as f64,
+            self.max_channel_difference,
+            self.mean_absolute_error,
+            self.mean_luma_error,
+            self.psnr,
+        )
+    }
+}
+
+fn luma(pixel: &Rgba<u8>) -> f64 {
+    0.2126 * pixel[0] as f64 + 0.7152 * pixel[1] as f64 + 0.0722 * pixel[2] as f64
+}
+
+/// Compare two images of the same size pixel by pixel.
+pub fn compare(expected: &RgbaImage, actual: &RgbaImage, tolerance: Tolerance) -> Comparison {
+    assert_eq!(
+        expected.dimensions(),
+        actual.dimensions(),
+        "Only images of the same size can be compared!"
+    );
+
+    let mut diff = RgbaImage::new(expected.width(), expected.height());
+    let mut differing_pixels = 0;
+    let mut max_channel_difference = 0;
+    let mut absolute_error = 0.;
+    let mut squared_error = 0.;
+    let mut luma_error = 0.;
+    for ((expected, actual), diff) in expected.pixels().zip(actual.pixels()).zip(diff.pixels_mut()) {
+        let pixel_difference = expected
+            .0
+            .iter()
+            .zip(actual.0.iter())
+            .map(|(e, a)| e.abs_diff(*a))
+            .max()
+            .unwrap_or(0);
+        for (e, a) in expected.0.iter().zip(actual.0.iter()) {
+            let d = e.abs_diff(*a) as f64;
+            absolute_error += d;
+            squared_error += d * d;
+        }
+        luma_error += (luma(expected) - luma(actual)).abs();
+        max_channel_difference = max_channel_difference.max(pixel_difference);
+
+        *diff = if pixel_difference > tolerance.channel {
+            differing_pixels += 1;
+            Rgba([255, 0, 0, 255])
+        } else {
+            let dimmed = (luma(expected) / 3.) as u8;
+            Rgba([dimmed, dimmed, dimmed, 255])
+        };
+    }
+
+    let total_pixels = (expected.width() * expected.height()) as usize;
+    let total_channels = (4 * total_pixels) as f64;
+    let mean_squared_error = squared_error / total_channels;
+    let psnr = if mean_squared_error == 0. {
+        f64::INFINITY
+    } else {
+        10. * (255. * 255. / mean_squared_error).log10()
+    };
+
+    Comparison {
+        differing_pixels,
+        total_pixels,
+        max_channel_difference,
+        mean_absolute_error: absolute_error / total_channels,
+        mean_luma_error: luma_error / total_pixels as f64,
+        psnr,
+        diff,
+    }
+}
+
+/// Render a frame of the dresser created by `new_dresser` on a headless viewport of the given
+/// size, and compare it against `<reference_dir>/<name>.png` with the default tolerance, see
+/// [`assert_matches_reference`].
+pub fn assert_dresser_matches_reference<Dresser: FrameDresser>(
+    reference_dir: impl AsRef<Path>,
+    output_dir: impl AsRef<Path>,
+    name: &str,
+    (width, height): (u32, u32),
+    new_dresser: impl FnOnce(&Viewport) -> Dresser,
+) {
+    let viewport = headless_viewport(width, height);
+    let mut dresser = new_dresser(&viewport);
+    let image = viewport
+        .capture(&mut dresser)
+        .expect("Error capturing frame!");
+
+    assert_matches_reference(
+        reference_dir,
+        output_dir,
+        name,
+        &image,
+        Tolerance::default(),
+    );
+}
+
+/// Compare `actual` against `<reference_dir>/<name>.png`, panicking with a summary of the
+/// differences if they exceed the tolerance. On failure the rendered and diff images are
+/// written to `output_dir` for inspection.
+///
+/// With `UPDATE_GOLDEN` set, the reference is written from `actual` instead, so it can be
+/// reviewed and checked in. A missing reference is never written implicitly, as that would
+/// bless whatever the first run happens to render.
+pub fn assert_matches_reference(
+    reference_dir: impl AsRef<Path>,
+    output_dir: impl AsRef<Path>,
+    name: &str,
+    actual: &RgbaImage,
+    tolerance: Tolerance,
+) {
+    let reference_dir = reference_dir.as_ref();
+    let reference_path = reference_dir.join(format!("{name}.png"));
+    let output_dir = output_dir.as_ref();
+    let write_output = |suffix: &str, image: &RgbaImage| -> PathBuf {
+        fs::create_dir_all(output_dir).expect("Error creating output directory!");
+        let path = output_dir.join(format!("{name}.{suffix}.png"));
+        image.save(&path).expect("Error writing output image!");
+        path
+    };
+
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        fs::create_dir_all(reference_dir).expect("Error creating reference directory!");
+        actual
+            .save(&reference_path)
+            .expect("Error writing reference image!");
+        return;
+    }
+    if !reference_path.exists() {
+        let actual_path = write_output("actual", actual);
+        panic!(
+            "No reference image for '{name}' at {}, rendered image written to {}. \
+            Generate the reference with UPDATE_GOLDEN=1, review it and check it in.",
+            reference_path.display(),
+            actual_path.display()
+        );
+    }
+
+    let expected = image::open(&reference_path)
+        .expect("Error reading reference image!")
+        .to_rgba8();
+    if expected.dimensions() != actual.dimensions() {
+        let actual_path = write_output("actual", actual);
+        panic!(
+            "Image '{name}' is {:?} but its reference is {:?}, rendered image written to {}",
+            actual.dimensions(),
+            expected.dimensions(),
+            actual_path.display()
+        );
+    }
+
+    let comparison = compare(&expected, actual, tolerance);
+    if !comparison.passes(tolerance) {
+        let actual_path = write_output("actual", actual);
+        let diff_path = write_output("diff", &comparison.diff);
+        panic!(
+            "Image '{name}' does not match its reference: {comparison}\n\
+            rendered image: {}\ndiff image: {}",
+            actual_path.display(),
+            diff_path.display()
+        );
+    }
+}
@@ -0,0 +1,121 @@
+use viewport::{Viewport, DresserHooks, RenderPassDresser, ShaderInterface, ShaderInterfaceError};
+
+pub struct MandelbrotDresser {
+    render_pipeline_layout: wgpu::PipelineLayout,
+    render_pipeline: wgpu::RenderPipeline,
+}
+
+impl MandelbrotDresser {
+    pub fn new(viewport: &Viewport) -> Self {
+        let device = viewport.device();
+
+        // Load shader, keeping its interface to check the pipeline against. naga doesn't parse
+        // everything rust-gpu emits, in which case the pipeline is created without the check
+        let spirv = wgpu::include_spirv_raw!("../../target/mandelbrot.spv");
+        let interface = ShaderInterface::from_spirv(&spirv.source)
+            .map_err(|e| log::warn!("Not checking the pipeline against the shader: {e}"))
+            .ok();
+        let shader = unsafe { device.create_shader_module_spirv(&spirv) };
+
+        // Create pipeline
+        let render_pipeline_layout =
+            device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: Some("Render Pipeline Layout"),
+                bind_group_layouts: &[],
+                push_constant_ranges: &[],
+            });
+        let render_pipeline = Self::create_render_pipeline(
+            viewport,
+            &render_pipeline_layout,
+            &shader,
+            interface.as_ref(),
+        )
+        .unwrap_or_else(|e| panic!("{e}"));
+
+        Self {
+            render_pipeline_layout,
+            render_pipeline,
+        }
+    }
+
+    fn create_render_pipeline(
+        viewport: &Viewport,
+        render_pipeline_layout: &wgpu::PipelineLayout,
+        shader: &wgpu::ShaderModule,
+        interface: Option<&ShaderInterface>,
+    ) -> Result<wgpu::RenderPipeline, ShaderInterfaceError> {
+        let targets = [Some(wgpu::ColorTargetState {
+            format: viewport.surface_format(),
+            blend: Some(wgpu::BlendState::REPLACE),
+            write_mask: wgpu::ColorWrites::ALL,
+        })];
+        let descriptor = wgpu::RenderPipelineDescriptor {
+            label: Some("Render Pipeline"),
+            layout: Some(render_pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: shader,
+                entry_point: "main_vs",
+                buffers: &[],
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: shader,
+                entry_point: "main_fs",
+                targets: &targets,
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                strip_index_format: None,
+                front_face: wgpu::FrontFace::Cw,
+                cull_mode: Some(wgpu::Face::Back),
+                // Setting this to anything other than Fill requires Features::NON_FILL_POLYGON_MODE
+                polygon_mode: wgpu::PolygonMode::Fill,
+                // Requires Features::DEPTH_CLIP_CONTROL
+                unclipped_depth: false,
+                // Requires Features::CONSERVATIVE_RASTERIZATION
+                conservative: false,
+            },
+            depth_stencil: viewport.depth_stencil_state(),
+            multisample: viewport.multisample_state(),
+            multiview: None,
+        };
+        if let Some(interface) = interface {
+            interface.validate_render_pipeline(&descriptor, &[])?;
+        }
+        Ok(viewport.device().create_render_pipeline(&descriptor))
+    }
+}
+
+impl DresserHooks for MandelbrotDresser {
+    fn reload_pipeline(
+        &self,
+        viewport: &Viewport,
+        shader: &str,
+        module: &wgpu::ShaderModule,
+        interface: &ShaderInterface,
+    ) -> Option<Result<wgpu::RenderPipeline, ShaderInterfaceError>> {
+        (shader == "mandelbrot").then(|| {
+            Self::create_render_pipeline(
+                viewport,
+                &self.render_pipeline_layout,
+                module,
+                Some(interface),
+            )
+        })
+    }
+
+    fn replace_pipeline(&mut self, _shader: &str, pipeline: wgpu::RenderPipeline) {
+        self.render_pipeline = pipeline;
+    }
+}
+
+impl RenderPassDresser for MandelbrotDresser {
+    fn color_load_op(&self) -> Option<wgpu::LoadOp<wgpu::Color>> {
+        // Every pixel is overwritten, so there is no need to clear the frame first
+        Some(wgpu::LoadOp::Load)
+    }
+
+    fn dress<'a, 'b>(&'a self, mut render_pass: wgpu::RenderPass<'b>) where 'a: 'b {
+        render_pass.set_pipeline(&self.render_pipeline);
+        render_pass.draw(0..3, 0..1);
+    }
+}
@@ -0,0 +1,6 @@
+use mandelbrot::MandelbrotDresser;
+
+#[test]
+fn mandelbrot_matches_reference() {
+    golden::assert_dresser_golden!("mandelbrot", (800, 800), MandelbrotDresser::new);
+}
@@ -0,0 +1,309 @@
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{format_ident, quote, quote_spanned};
+use syn::{
+    parse_macro_input, spanned::Spanned, Data, DeriveInput, Error, Fields, Ident, LitInt, LitStr,
+    Type,
+};
+
+/// Implement `models::Vertex` for a `#[repr(C)]` struct, with one vertex attribute per field.
+///
+/// Attributes get consecutive shader locations in field order, starting at 0, and a format
+/// inferred from the field type (`f32`, `[f32; 2]`, `[u8; 4]`, ...). Both can be overridden per
+/// field, and fields that aren't read by the shader (e.g. padding) can be skipped:
+///
+/// ```ignore
+/// #[repr(C)]
+/// #[derive(Copy, Clone, bytemuck::Pod, bytemuck::Zeroable, Vertex)]
+/// #[vertex(step_mode = "instance")]
+/// struct Instance {
+///     #[vertex(location = 5)]
+///     offset: [f32; 3],
+///     #[vertex(skip)]
+///     _padding: u32,
+///     #[vertex(format = "Unorm8x4")]
+///     color: [u8; 4],
+/// }
+/// ```
+///
+/// Locations continue counting from an overridden one, so `color` above ends up at location 6.
+/// Two attributes at the same location are a compile error.
+#[proc_macro_derive(Vertex, attributes(vertex))]
+pub fn derive_vertex(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    derive(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+struct Attribute {
+    location: u32,
+    format: TokenStream2,
+}
+
+fn derive(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+    if !input.generics.params.is_empty() {
+        return Err(Error::new(
+            input.generics.span(),
+            "Vertex can't be derived for generic structs",
+        ));
+    }
+    check_repr_c(&input)?;
+
+    let mut step_mode = quote!(Vertex);
+    for attr in input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("vertex"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("step_mode") {
+                let value: LitStr = meta.value()?.parse()?;
+                step_mode = match value.value().as_str() {
+                    "vertex" => quote!(Vertex),
+                    "instance" => quote!(Instance),
+                    _ => {
+                        return Err(Error::new(
+                            value.span(),
+                            "expected \"vertex\" or \"instance\"",
+                        ))
+                    }
+                };
+                Ok(())
+            } else {
+                Err(meta.error("unknown vertex attribute, expected `step_mode`"))
+            }
+        })?;
+    }
+
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            Fields::Unnamed(fields) => &fields.unnamed,
+            Fields::Unit => {
+                return Err(Error::new(
+                    input.span(),
+                    "Vertex can't be derived for unit structs",
+                ))
+            }
+        },
+        _ => {
+            return Err(Error::new(
+                input.span(),
+                "Vertex can only be derived for structs",
+            ))
+        }
+    };
+
+    // Offsets follow the #[repr(C)] layout: every field starts at the end of the previous one,
+    // rounded up to its alignment
+    let offsets = (0..fields.len())
+        .map(|i| format_ident!("OFFSET_{}", i))
+        .collect::<Vec<_>>();
+    let mut offset_consts = vec![];
+    for (i, field) in fields.iter().enumerate() {
+        let offset = &offsets[i];
+        let ty = &field.ty;
+        let value = match i {
+            0 => quote!(0),
+            _ => {
+                let previous = &offsets[i - 1];
+                let previous_ty = &fields[i - 1].ty;
+                quote! {{
+                    let end = #previous + ::std::mem::size_of::<#previous_ty>();
+                    let align = ::std::mem::align_of::<#ty>();
+                    (end + align - 1) & !(align - 1)
+                }}
+            }
+        };
+        offset_consts.push(quote!(const #offset: usize = #value;));
+    }
+
+    let mut location = 0;
+    let mut used_locations: Vec<(u32, &syn::Field)> = vec![];
+    let mut attributes = vec![];
+    let mut size_checks = vec![];
+    for (field, offset) in fields.iter().zip(&offsets) {
+        let Some(Attribute {
+            location: field_location,
+            format,
+        }) = field_attribute(field, location)?
+        else {
+            continue;
+        };
+        if let Some((_, other)) = used_locations
+            .iter()
+            .find(|(used, _)| *used == field_location)
+        {
+            let other = match &other.ident {
+                Some(ident) => format!("`{ident}`"),
+                None => "another field".to_string(),
+            };
+            let span = field
+                .ident
+                .as_ref()
+                .map_or_else(|| field.ty.span(), Ident::span);
+            return Err(Error::new(
+                span,
+                format!("location {field_location} is already used by {other}"),
+            ));
+        }
+        used_locations.push((field_location, field));
+        location = field_location + 1;
+
+        let ty = &field.ty;
+        size_checks.push(quote_spanned! {ty.span()=>
+            const _: () = assert!(
+                ::wgpu::VertexFormat::#format.size() <= ::std::mem::size_of::<#ty>() as u64,
+                "the vertex format is larger than the field",
+            );
+        });
+        attributes.push(quote! {
+            ::wgpu::VertexAttribute {
+                format: ::wgpu::VertexFormat::#format,
+                offset: #offset as ::wgpu::BufferAddress,
+                shader_location: #field_location,
+            }
+        });
+    }
+
+    Ok(quote! {
+        impl ::models::Vertex for #name {
+            fn desc<'a>() -> ::wgpu::VertexBufferLayout<'a> {
+                #(#offset_consts)*
+                #(#size_checks)*
+                const ATTRIBUTES: &[::wgpu::VertexAttribute] = &[#(#attributes),*];
+
+                ::wgpu::VertexBufferLayout {
+                    array_stride: ::std::mem::size_of::<#name>() as ::wgpu::BufferAddress,
+                    step_mode: ::wgpu::VertexStepMode::#step_mode,
+                    attributes: ATTRIBUTES,
+                }
+            }
+        }
+    })
+}
+
+/// Offsets are only predictable with `#[repr(C)]`, and `packed` would break the alignment rules
+/// they are computed with.
+fn check_repr_c(input: &DeriveInput) -> syn::Result<()> {
+    let mut repr_c = false;
+    for attr in input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("repr"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("C") {
+                repr_c = true;
+            } else if meta.path.is_ident("packed") {
+                return Err(meta.error("Vertex can't be derived for packed structs"));
+            } else if meta.input.peek(syn::token::Paren) {
+                // e.g. align(16), which only adds trailing padding
+                let _ = meta.input.parse::<proc_macro2::Group>()?;
+            }
+            Ok(())
+        })?;
+    }
+    if repr_c {
+        Ok(())
+    } else {
+        Err(Error::new(
+            Span::call_site(),
+            "Vertex can only be derived for #[repr(C)] structs",
+        ))
+    }
+}
+
+/// The attribute of a field given the next free location, `None` if the field is skipped.
+fn field_attribute(field: &syn::Field, location: u32) -> syn::Result<Option<Attribute>> {
+    let mut location = location;
+    let mut format = None;
+    let mut skip = false;
+    for attr in field
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("vertex"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("location") {
+                location = meta.value()?.parse::<LitInt>()?.base10_parse()?;
+                Ok(())
+            } else if meta.path.is_ident("format") {
+                let value: LitStr = meta.value()?.parse()?;
+                format = Some(value.parse::<Ident>()?);
+                Ok(())
+            } else if meta.path.is_ident("skip") {
+                skip = true;
+                Ok(())
+            } else {
+                Err(meta.error("unknown vertex attribute, expected `location`, `format` or `skip`"))
+            }
+        })?;
+    }
+    if skip {
+        return Ok(None);
+    }
+
+    let format = match format {
+        Some(format) => quote!(#format),
+        None => infer_format(&field.ty).ok_or_else(|| {
+            Error::new(
+                field.ty.span(),
+                "can't infer the vertex format of this type, \
+                specify it with #[vertex(format = \"...\")]",
+            )
+        })?,
+    };
+    Ok(Some(Attribute { location, format }))
+}
+
+/// The format of scalars and arrays of up to 4 scalars, where wgpu has one. Integers are
+/// read as integers, use an explicit format for normalized ones.
+fn infer_format(ty: &Type) -> Option<TokenStream2> {
+    let (scalar, components) = match ty {
+        Type::Path(path) => (path.path.get_ident()?.to_string(), 1),
+        Type::Array(array) => {
+            let Type::Path(path) = &*array.elem else {
+                return None;
+            };
+            let syn::Expr::Lit(syn::ExprLit {
+                lit: syn::Lit::Int(len),
+                ..
+            }) = &array.len
+            else {
+                return None;
+            };
+            (path.path.get_ident()?.to_string(), len.base10_parse().ok()?)
+        }
+        _ => return None,
+    };
+
+    let format = match (scalar.as_str(), components) {
+        ("f32" | "u32" | "i32" | "f64", 1..=4) => {
+            let scalar = match scalar.as_str() {
+                "f32" => "Float32",
+                "u32" => "Uint32",
+                "i32" => "Sint32",
+                _ => "Float64",
+            };
+            match components {
+                1 => scalar.to_string(),
+                n => format!("{scalar}x{n}"),
+            }
+        }
+        ("u8" | "i8" | "u16" | "i16", 2 | 4) => {
+            let scalar = match scalar.as_str() {
+                "u8" => "Uint8",
+                "i8" => "Sint8",
+                "u16" => "Uint16",
+                _ => "Sint16",
+            };
+            format!("{scalar}x{components}")
+        }
+        _ => return None,
+    };
+    let format = Ident::new(&format, ty.span());
+    Some(quote!(#format))
+}
@@ -1,5 +1,17 @@
+// Lets the code generated by models-derive refer to `::models` from inside this crate too
+extern crate self as models;
+
+mod math;
 mod vertex;
 mod texture;
+mod mesh;
+mod obj;
+mod scene;
+pub mod primitives;
 
-pub use vertex::{ColoredVertex, TexturedVertex};
-pub use texture::Texture;
\ No newline at end of file
+pub use vertex::{ColoredVertex, ModelVertex, TexturedVertex, Vertex};
+pub use models_derive::Vertex;
+pub use texture::Texture;
+pub use mesh::{GpuMesh, Indices, Mesh};
+pub use obj::{ObjError, ObjErrorKind, ObjMaterial, ObjMesh, ObjModel};
+pub use scene::{Material, Node, Primitive, Scene, SceneError, SceneMesh};
@@ -0,0 +1,59 @@
+//! The little vector math models needs, on plain arrays as stored in vertices.
+
+pub(crate) type Vec3 = [f32; 3];
+
+/// Column major, as in glTF and WGSL.
+pub(crate) type Mat4 = [[f32; 4]; 4];
+
+pub(crate) const IDENTITY: Mat4 = [
+    [1.0, 0.0, 0.0, 0.0],
+    [0.0, 1.0, 0.0, 0.0],
+    [0.0, 0.0, 1.0, 0.0],
+    [0.0, 0.0, 0.0, 1.0],
+];
+
+pub(crate) fn add(a: Vec3, b: Vec3) -> Vec3 {
+    [a[0] + b[0], a[1] + b[1], a[2] + b[2]]
+}
+
+pub(crate) fn sub(a: Vec3, b: Vec3) -> Vec3 {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+pub(crate) fn scale(a: Vec3, s: f32) -> Vec3 {
+    a.map(|c| c * s)
+}
+
+pub(crate) fn dot(a: Vec3, b: Vec3) -> f32 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+pub(crate) fn cross(a: Vec3, b: Vec3) -> Vec3 {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+/// `a` scaled to unit length, `None` if it has (almost) no length.
+pub(crate) fn normalize(a: Vec3) -> Option<Vec3> {
+    let length = dot(a, a).sqrt();
+    (length > 1e-12).then(|| scale(a, 1.0 / length))
+}
+
+/// Any unit vector perpendicular to the unit vector `a`.
+pub(crate) fn perpendicular(a: Vec3) -> Vec3 {
+    let axis = if a[0].abs() < 0.9 {
+        [1.0, 0.0, 0.0]
+    } else {
+        [0.0, 1.0, 0.0]
+    };
+    normalize(cross(a, axis)).unwrap()
+}
+
+pub(crate) fn mul(a: &Mat4, b: &Mat4) -> Mat4 {
+    std::array::from_fn(|column| {
+        std::array::from_fn(|row| (0..4).map(|i| a[i][row] * b[column][i]).sum())
+    })
+}
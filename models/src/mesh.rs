@@ -0,0 +1,189 @@
+use wgpu::util::DeviceExt;
+
+use crate::{
+    math::{add, cross, dot, normalize, perpendicular, scale, sub},
+    ModelVertex, Vertex,
+};
+
+/// Indices of a mesh, stored as `u16` whenever all vertices can be addressed with them.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Indices {
+    U16(Vec<u16>),
+    U32(Vec<u32>),
+}
+
+impl Indices {
+    pub fn len(&self) -> usize {
+        match self {
+            Indices::U16(indices) => indices.len(),
+            Indices::U32(indices) => indices.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn format(&self) -> wgpu::IndexFormat {
+        match self {
+            Indices::U16(_) => wgpu::IndexFormat::Uint16,
+            Indices::U32(_) => wgpu::IndexFormat::Uint32,
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = u32> + '_ {
+        let (u16s, u32s) = match self {
+            Indices::U16(indices) => (&indices[..], &[][..]),
+            Indices::U32(indices) => (&[][..], &indices[..]),
+        };
+        u16s.iter()
+            .map(|&index| index as u32)
+            .chain(u32s.iter().copied())
+    }
+
+    fn as_bytes(&self) -> &[u8] {
+        match self {
+            Indices::U16(indices) => bytemuck::cast_slice(indices),
+            Indices::U32(indices) => bytemuck::cast_slice(indices),
+        }
+    }
+}
+
+/// An indexed triangle list on the CPU.
+#[derive(Clone, Debug)]
+pub struct Mesh<V> {
+    pub(crate) vertices: Vec<V>,
+    pub(crate) indices: Indices,
+}
+
+impl<V: Vertex> Mesh<V> {
+    /// Panics if an index is out of bounds.
+    pub fn new(vertices: Vec<V>, indices: Vec<u32>) -> Self {
+        if let Some(index) = indices
+            .iter()
+            .find(|&&index| index as usize >= vertices.len())
+        {
+            panic!(
+                "Index {index} is out of bounds for a mesh with {} vertices",
+                vertices.len()
+            );
+        }
+        // u16::MAX itself is left out, as it restarts strips
+        let indices = if vertices.len() <= u16::MAX as usize {
+            Indices::U16(indices.into_iter().map(|index| index as u16).collect())
+        } else {
+            Indices::U32(indices)
+        };
+        Self { vertices, indices }
+    }
+
+    pub fn vertices(&self) -> &[V] {
+        &self.vertices
+    }
+
+    pub fn indices(&self) -> &Indices {
+        &self.indices
+    }
+
+    /// Convert every vertex, e.g. to leave out attributes a shader doesn't read.
+    pub fn map_vertices<W: Vertex>(self, f: impl FnMut(V) -> W) -> Mesh<W> {
+        Mesh {
+            vertices: self.vertices.into_iter().map(f).collect(),
+            indices: self.indices,
+        }
+    }
+
+    /// Create the vertex and index buffers of this mesh.
+    pub fn upload(&self, device: &wgpu::Device, label: &str) -> GpuMesh {
+        let vertex_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some(&format!("{label} vertex buffer")),
+            contents: bytemuck::cast_slice(&self.vertices),
+            usage: wgpu::BufferUsages::VERTEX,
+        });
+        let index_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some(&format!("{label} index buffer")),
+            contents: self.indices.as_bytes(),
+            usage: wgpu::BufferUsages::INDEX,
+        });
+
+        GpuMesh {
+            vertex_buffer,
+            index_buffer,
+            index_format: self.indices.format(),
+            num_indices: self.indices.len() as u32,
+        }
+    }
+}
+
+impl Mesh<ModelVertex> {
+    /// Compute the tangents of all vertices from their texture coordinates, averaged over the
+    /// triangles sharing them. Vertices whose triangles have no usable texture coordinates get
+    /// an arbitrary tangent perpendicular to their normal.
+    pub fn compute_tangents(&mut self) {
+        let mut tangents = vec![[0.0; 3]; self.vertices.len()];
+        let mut bitangents = vec![[0.0; 3]; self.vertices.len()];
+        let indices = self.indices.iter().collect::<Vec<_>>();
+        for triangle in indices.chunks_exact(3) {
+            let [a, b, c] = [0, 1, 2].map(|i| self.vertices[triangle[i] as usize]);
+            let edges = [sub(b.position, a.position), sub(c.position, a.position)];
+            let [du1, dv1] = [0, 1].map(|i| b.texture_coords[i] - a.texture_coords[i]);
+            let [du2, dv2] = [0, 1].map(|i| c.texture_coords[i] - a.texture_coords[i]);
+            let determinant = du1 * dv2 - du2 * dv1;
+            if determinant.abs() < 1e-12 {
+                continue;
+            }
+            let tangent = scale(
+                sub(scale(edges[0], dv2), scale(edges[1], dv1)),
+                1.0 / determinant,
+            );
+            let bitangent = scale(
+                sub(scale(edges[1], du1), scale(edges[0], du2)),
+                1.0 / determinant,
+            );
+            for &index in triangle {
+                tangents[index as usize] = add(tangents[index as usize], tangent);
+                bitangents[index as usize] = add(bitangents[index as usize], bitangent);
+            }
+        }
+
+        for ((vertex, tangent), bitangent) in self.vertices.iter_mut().zip(tangents).zip(bitangents)
+        {
+            let normal = vertex.normal;
+            // Make the tangent perpendicular to the normal
+            let tangent = normalize(sub(tangent, scale(normal, dot(normal, tangent))))
+                .unwrap_or_else(|| normalize(normal).map_or([1.0, 0.0, 0.0], perpendicular));
+            let handedness = if dot(cross(normal, tangent), bitangent) < 0.0 {
+                -1.0
+            } else {
+                1.0
+            };
+            vertex.tangent = [tangent[0], tangent[1], tangent[2], handedness];
+        }
+    }
+}
+
+/// A mesh uploaded to the GPU, ready to be drawn.
+pub struct GpuMesh {
+    pub vertex_buffer: wgpu::Buffer,
+    pub index_buffer: wgpu::Buffer,
+    pub index_format: wgpu::IndexFormat,
+    pub num_indices: u32,
+}
+
+impl GpuMesh {
+    /// Draw the mesh with the vertex buffer in slot 0, using the pipeline and bind groups already
+    /// set on the render pass.
+    pub fn draw<'a>(&'a self, render_pass: &mut wgpu::RenderPass<'a>) {
+        self.draw_instanced(render_pass, 0..1);
+    }
+
+    pub fn draw_instanced<'a>(
+        &'a self,
+        render_pass: &mut wgpu::RenderPass<'a>,
+        instances: std::ops::Range<u32>,
+    ) {
+        render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
+        render_pass.set_index_buffer(self.index_buffer.slice(..), self.index_format);
+        render_pass.draw_indexed(0..self.num_indices, 0, instances);
+    }
+}
@@ -0,0 +1,684 @@
+use std::{
+    collections::HashMap,
+    fmt, fs, io,
+    path::{Path, PathBuf},
+    str::{FromStr, SplitWhitespace},
+};
+
+use crate::{Mesh, ModelVertex};
+
+#[derive(Debug)]
+pub enum ObjError {
+    /// An OBJ file or material library could not be read.
+    Io { path: PathBuf, error: io::Error },
+    /// A line of an OBJ file, or of the material library `file`, is malformed.
+    Parse {
+        file: Option<PathBuf>,
+        line: usize,
+        kind: ObjErrorKind,
+    },
+}
+
+/// What is wrong with a malformed line.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ObjErrorKind {
+    /// A statement has fewer arguments than it requires.
+    MissingArguments {
+        keyword: String,
+        expected: usize,
+        found: usize,
+    },
+    /// An argument that should be a number isn't one.
+    InvalidNumber(String),
+    /// A face vertex isn't of the form `v`, `v/vt`, `v//vn` or `v/vt/vn`.
+    InvalidFaceVertex(String),
+    /// A face refers to an element that isn't defined (yet).
+    IndexOutOfBounds {
+        element: &'static str,
+        index: i64,
+        count: usize,
+    },
+    /// `usemtl` names a material none of the material libraries define.
+    UnknownMaterial(String),
+    /// A material library has a statement before its first `newmtl`.
+    NoMaterial(String),
+}
+
+impl fmt::Display for ObjError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ObjError::Io { path, error } => {
+                write!(f, "Error reading {}: {error}", path.display())
+            }
+            ObjError::Parse { file, line, kind } => {
+                if let Some(file) = file {
+                    write!(f, "{}, ", file.display())?;
+                }
+                write!(f, "line {line}: {kind}")
+            }
+        }
+    }
+}
+
+impl fmt::Display for ObjErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ObjErrorKind::MissingArguments {
+                keyword,
+                expected,
+                found,
+            } => write!(
+                f,
+                "'{keyword}' takes at least {expected} argument(s), found {found}"
+            ),
+            ObjErrorKind::InvalidNumber(value) => write!(f, "'{value}' is not a number"),
+            ObjErrorKind::InvalidFaceVertex(value) => {
+                write!(f, "'{value}' is not a valid face vertex")
+            }
+            ObjErrorKind::IndexOutOfBounds {
+                element,
+                index,
+                count,
+            } => write!(
+                f,
+                "{element} index {index} is out of bounds, {count} {element}(s) are defined"
+            ),
+            ObjErrorKind::UnknownMaterial(name) => write!(f, "Unknown material '{name}'"),
+            ObjErrorKind::NoMaterial(keyword) => {
+                write!(f, "'{keyword}' comes before any 'newmtl'")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ObjError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ObjError::Io { error, .. } => Some(error),
+            ObjError::Parse { .. } => None,
+        }
+    }
+}
+
+/// A material from an MTL library. Texture paths are relative to the OBJ file's directory when
+/// loaded with [`ObjModel::load`], and as written in the library otherwise.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ObjMaterial {
+    pub name: String,
+    pub ambient: [f32; 3],
+    pub diffuse: [f32; 3],
+    pub specular: [f32; 3],
+    pub shininess: f32,
+    pub dissolve: f32,
+    pub diffuse_texture: Option<PathBuf>,
+    pub normal_texture: Option<PathBuf>,
+}
+
+impl ObjMaterial {
+    fn new(name: String) -> Self {
+        Self {
+            name,
+            ambient: [0.0; 3],
+            diffuse: [1.0; 3],
+            specular: [0.0; 3],
+            shininess: 0.0,
+            dissolve: 1.0,
+            diffuse_texture: None,
+            normal_texture: None,
+        }
+    }
+}
+
+/// The faces of an object or group using one material.
+#[derive(Clone, Debug)]
+pub struct ObjMesh {
+    /// Name of the object or group the faces belong to, empty if there is none.
+    pub name: String,
+    pub mesh: Mesh<ModelVertex>,
+    /// Index into [`ObjModel::materials`].
+    pub material: Option<usize>,
+}
+
+/// The meshes and materials of a Wavefront OBJ file.
+///
+/// Polygons are triangulated as fans, and vertices sharing their position, texture coordinates
+/// and normal are merged. Faces without normals get flat ones, faces without texture coordinates
+/// get (0, 0), and tangents are computed from the texture coordinates. Texture coordinates are
+/// flipped vertically, as wgpu has its origin at the top left.
+/// Statements other than `v`, `vt`, `vn`, `f`, `o`, `g`, `mtllib` and `usemtl` are ignored.
+#[derive(Clone, Debug)]
+pub struct ObjModel {
+    pub meshes: Vec<ObjMesh>,
+    pub materials: Vec<ObjMaterial>,
+}
+
+impl ObjModel {
+    /// Load an OBJ file and the material libraries it refers to.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ObjError> {
+        let path = path.as_ref();
+        let dir = path.parent().unwrap_or(Path::new(""));
+        let read = |path: &Path| {
+            fs::read_to_string(path).map_err(|error| ObjError::Io {
+                path: path.to_owned(),
+                error,
+            })
+        };
+
+        let source = read(path)?;
+        let mut model = Self::parse(&source, |mtl| read(&dir.join(mtl))).map_err(|e| match e {
+            ObjError::Parse {
+                file: None,
+                line,
+                kind,
+            } => ObjError::Parse {
+                file: Some(path.to_owned()),
+                line,
+                kind,
+            },
+            e => e,
+        })?;
+
+        for material in &mut model.materials {
+            let textures = [&mut material.diffuse_texture, &mut material.normal_texture];
+            for texture in textures.into_iter().flatten() {
+                *texture = dir.join(&*texture);
+            }
+        }
+        Ok(model)
+    }
+
+    /// Parse the contents of an OBJ file, calling `read_mtl` with the name of every material
+    /// library it refers to.
+    pub fn parse(
+        source: &str,
+        mut read_mtl: impl FnMut(&str) -> Result<String, ObjError>,
+    ) -> Result<Self, ObjError> {
+        let mut positions = vec![];
+        let mut texture_coords = vec![];
+        let mut normals = vec![];
+        let mut materials = vec![];
+        let mut meshes = vec![];
+        let mut builder = MeshBuilder::new(String::new(), None);
+
+        for (i, line) in source.lines().enumerate() {
+            let error = |kind| ObjError::Parse {
+                file: None,
+                line: i + 1,
+                kind,
+            };
+            let mut statement = Statement::new(line);
+            let Some(keyword) = statement.keyword else {
+                continue;
+            };
+
+            match keyword {
+                "v" => positions.push(statement.floats::<3>(3).map_err(error)?),
+                "vt" => {
+                    let [u, v] = statement.floats::<2>(1).map_err(error)?;
+                    texture_coords.push([u, 1.0 - v]);
+                }
+                "vn" => normals.push(statement.floats::<3>(3).map_err(error)?),
+                "f" => {
+                    let face = statement
+                        .arguments
+                        .by_ref()
+                        .map(|vertex| {
+                            face_vertex(
+                                vertex,
+                                positions.len(),
+                                texture_coords.len(),
+                                normals.len(),
+                            )
+                        })
+                        .collect::<Result<Vec<_>, _>>()
+                        .map_err(error)?;
+                    if face.len() < 3 {
+                        return Err(error(ObjErrorKind::MissingArguments {
+                            keyword: keyword.to_string(),
+                            expected: 3,
+                            found: face.len(),
+                        }));
+                    }
+                    builder.add_face(&face, &positions, &texture_coords, &normals);
+                }
+                "o" | "g" => {
+                    let name = statement.rest();
+                    let material = builder.material;
+                    meshes.extend(builder.build());
+                    builder = MeshBuilder::new(name, material);
+                }
+                "usemtl" => {
+                    let name = statement.rest();
+                    let material = materials
+                        .iter()
+                        .position(|material: &ObjMaterial| material.name == name)
+                        .ok_or_else(|| error(ObjErrorKind::UnknownMaterial(name)))?;
+                    let name = builder.name.clone();
+                    meshes.extend(builder.build());
+                    builder = MeshBuilder::new(name, Some(material));
+                }
+                "mtllib" => {
+                    for file in statement.arguments {
+                        let source = read_mtl(file)?;
+                        materials.extend(parse_mtl(&source).map_err(|e| match e {
+                            ObjError::Parse {
+                                file: None,
+                                line,
+                                kind,
+                            } => ObjError::Parse {
+                                file: Some(file.into()),
+                                line,
+                                kind,
+                            },
+                            e => e,
+                        })?);
+                    }
+                }
+                _ => {}
+            }
+        }
+        meshes.extend(builder.build());
+
+        Ok(Self { meshes, materials })
+    }
+}
+
+/// Parse the contents of an MTL material library.
+fn parse_mtl(source: &str) -> Result<Vec<ObjMaterial>, ObjError> {
+    let mut materials: Vec<ObjMaterial> = vec![];
+
+    for (i, line) in source.lines().enumerate() {
+        let error = |kind| ObjError::Parse {
+            file: None,
+            line: i + 1,
+            kind,
+        };
+        let mut statement = Statement::new(line);
+        let Some(keyword) = statement.keyword else {
+            continue;
+        };
+
+        if keyword == "newmtl" {
+            materials.push(ObjMaterial::new(statement.rest()));
+            continue;
+        }
+        let Some(material) = materials.last_mut() else {
+            return Err(error(ObjErrorKind::NoMaterial(keyword.to_string())));
+        };
+        match keyword {
+            "Ka" => material.ambient = statement.floats::<3>(3).map_err(error)?,
+            "Kd" => material.diffuse = statement.floats::<3>(3).map_err(error)?,
+            "Ks" => material.specular = statement.floats::<3>(3).map_err(error)?,
+            "Ns" => material.shininess = statement.floats::<1>(1).map_err(error)?[0],
+            "d" => material.dissolve = statement.floats::<1>(1).map_err(error)?[0],
+            "Tr" => material.dissolve = 1.0 - statement.floats::<1>(1).map_err(error)?[0],
+            "map_Kd" => material.diffuse_texture = Some(statement.texture().map_err(error)?),
+            "map_Bump" | "map_bump" | "bump" | "norm" => {
+                material.normal_texture = Some(statement.texture().map_err(error)?)
+            }
+            _ => {}
+        }
+    }
+
+    Ok(materials)
+}
+
+/// A line split into its keyword and arguments, without comments.
+struct Statement<'a> {
+    keyword: Option<&'a str>,
+    arguments: SplitWhitespace<'a>,
+    line: &'a str,
+}
+
+impl<'a> Statement<'a> {
+    fn new(line: &'a str) -> Self {
+        let line = line.split('#').next().unwrap().trim();
+        let mut arguments = line.split_whitespace();
+        Self {
+            keyword: arguments.next(),
+            arguments,
+            line,
+        }
+    }
+
+    /// The first `N` arguments as numbers, of which only `required` have to be present. Any
+    /// more are ignored, e.g. the optional `w` of positions.
+    fn floats<const N: usize>(&mut self, required: usize) -> Result<[f32; N], ObjErrorKind> {
+        let mut values = [0.0; N];
+        for (i, value) in values.iter_mut().enumerate() {
+            match self.arguments.next() {
+                Some(argument) => *value = parse_number(argument)?,
+                None if i < required => {
+                    return Err(ObjErrorKind::MissingArguments {
+                        keyword: self.keyword.unwrap_or_default().to_string(),
+                        expected: required,
+                        found: i,
+                    })
+                }
+                None => break,
+            }
+        }
+        Ok(values)
+    }
+
+    /// Everything after the keyword, e.g. names containing spaces.
+    fn rest(&self) -> String {
+        let keyword = self.keyword.unwrap_or_default();
+        self.line[keyword.len()..].trim().to_string()
+    }
+
+    /// The path of a texture map, which comes after its options.
+    fn texture(&mut self) -> Result<PathBuf, ObjErrorKind> {
+        self.arguments
+            .by_ref()
+            .last()
+            .map(PathBuf::from)
+            .ok_or_else(|| ObjErrorKind::MissingArguments {
+                keyword: self.keyword.unwrap_or_default().to_string(),
+                expected: 1,
+                found: 0,
+            })
+    }
+}
+
+fn parse_number<T: FromStr>(value: &str) -> Result<T, ObjErrorKind> {
+    value
+        .parse()
+        .map_err(|_| ObjErrorKind::InvalidNumber(value.to_string()))
+}
+
+/// Zero based indices of a face vertex's position, texture coordinates and normal.
+type FaceVertex = (usize, Option<usize>, Option<usize>);
+
+fn face_vertex(
+    vertex: &str,
+    positions: usize,
+    texture_coords: usize,
+    normals: usize,
+) -> Result<FaceVertex, ObjErrorKind> {
+    let mut indices = vertex.split('/');
+    let position = match indices.next() {
+        Some(index) if !index.is_empty() => index,
+        _ => return Err(ObjErrorKind::InvalidFaceVertex(vertex.to_string())),
+    };
+    let texture_coord = indices.next().filter(|index| !index.is_empty());
+    let normal = indices.next().filter(|index| !index.is_empty());
+    if indices.next().is_some() {
+        return Err(ObjErrorKind::InvalidFaceVertex(vertex.to_string()));
+    }
+
+    Ok((
+        resolve_index(position, "position", positions)?,
+        texture_coord
+            .map(|index| resolve_index(index, "texture coordinate", texture_coords))
+            .transpose()?,
+        normal
+            .map(|index| resolve_index(index, "normal", normals))
+            .transpose()?,
+    ))
+}
+
+/// Indices start at 1, negative ones count back from the last element defined so far.
+fn resolve_index(index: &str, element: &'static str, count: usize) -> Result<usize, ObjErrorKind> {
+    let index: i64 = parse_number(index)?;
+    let resolved = if index > 0 {
+        index - 1
+    } else {
+        count as i64 + index
+    };
+    if index == 0 || resolved < 0 || resolved >= count as i64 {
+        return Err(ObjErrorKind::IndexOutOfBounds {
+            element,
+            index,
+            count,
+        });
+    }
+    Ok(resolved as usize)
+}
+
+/// Either the index of a normal from the file, or the bits of a computed face normal.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+enum NormalKey {
+    Index(usize),
+    Face([u32; 3]),
+}
+
+struct MeshBuilder {
+    name: String,
+    material: Option<usize>,
+    vertices: Vec<ModelVertex>,
+    indices: Vec<u32>,
+    unique: HashMap<(usize, Option<usize>, NormalKey), u32>,
+}
+
+impl MeshBuilder {
+    fn new(name: String, material: Option<usize>) -> Self {
+        Self {
+            name,
+            material,
+            vertices: vec![],
+            indices: vec![],
+            unique: HashMap::new(),
+        }
+    }
+
+    fn add_face(
+        &mut self,
+        face: &[FaceVertex],
+        positions: &[[f32; 3]],
+        texture_coords: &[[f32; 2]],
+        normals: &[[f32; 3]],
+    ) {
+        let face_normal = polygon_normal(face.iter().map(|&(position, _, _)| positions[position]));
+
+        let indices = face
+            .iter()
+            .map(|&(position, texture_coord, normal)| {
+                let normal_key = match normal {
+                    Some(normal) => NormalKey::Index(normal),
+                    None => NormalKey::Face(face_normal.map(f32::to_bits)),
+                };
+                *self
+                    .unique
+                    .entry((position, texture_coord, normal_key))
+                    .or_insert_with(|| {
+                        self.vertices.push(ModelVertex {
+                            position: positions[position],
+                            normal: normal.map_or(face_normal, |normal| normals[normal]),
+                            texture_coords: texture_coord
+                                .map_or([0.0; 2], |texture_coord| texture_coords[texture_coord]),
+                            tangent: [0.0; 4],
+                        });
+                        self.vertices.len() as u32 - 1
+                    })
+            })
+            .collect::<Vec<_>>();
+
+        for i in 1..indices.len() - 1 {
+            self.indices
+                .extend_from_slice(&[indices[0], indices[i], indices[i + 1]]);
+        }
+    }
+
+    fn build(self) -> Option<ObjMesh> {
+        if self.indices.is_empty() {
+            return None;
+        }
+        let mut mesh = Mesh::new(self.vertices, self.indices);
+        mesh.compute_tangents();
+        Some(ObjMesh {
+            name: self.name,
+            mesh,
+            material: self.material,
+        })
+    }
+}
+
+/// Newell's method, which also works for polygons that are slightly non-planar.
+fn polygon_normal(positions: impl Iterator<Item = [f32; 3]> + Clone) -> [f32; 3] {
+    let mut normal = [0.0f32; 3];
+    let next = positions.clone().cycle().skip(1);
+    for (current, next) in positions.zip(next) {
+        normal[0] += (current[1] - next[1]) * (current[2] + next[2]);
+        normal[1] += (current[2] - next[2]) * (current[0] + next[0]);
+        normal[2] += (current[0] - next[0]) * (current[1] + next[1]);
+    }
+    let length = normal.iter().map(|c| c * c).sum::<f32>().sqrt();
+    if length > 0.0 {
+        normal.map(|c| c / length)
+    } else {
+        normal
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Indices;
+
+    fn parse(source: &str) -> Result<ObjModel, ObjError> {
+        ObjModel::parse(source, |file| panic!("Unexpected material library {file}"))
+    }
+
+    fn error_kind(source: &str) -> (usize, ObjErrorKind) {
+        match parse(source) {
+            Err(ObjError::Parse { line, kind, .. }) => (line, kind),
+            result => panic!("Expected a parse error, got {result:?}"),
+        }
+    }
+
+    #[test]
+    fn triangulates_polygons_as_fans() {
+        let model = parse(
+            "v 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 1 0\n\
+            vt 0 0\nvt 1 0\nvt 1 1\nvt 0 1\n\
+            vn 0 0 1\n\
+            f 1/1/1 2/2/1 3/3/1 4/4/1\n",
+        )
+        .unwrap();
+
+        assert_eq!(model.meshes.len(), 1);
+        let mesh = &model.meshes[0].mesh;
+        assert_eq!(mesh.vertices().len(), 4);
+        assert_eq!(mesh.indices(), &Indices::U16(vec![0, 1, 2, 0, 2, 3]));
+        assert_eq!(
+            mesh.vertices()[2],
+            ModelVertex {
+                position: [1.0, 1.0, 0.0],
+                normal: [0.0, 0.0, 1.0],
+                texture_coords: [1.0, 0.0],
+                // v points down after flipping, so the bitangent is -y
+                tangent: [1.0, 0.0, 0.0, -1.0],
+            }
+        );
+    }
+
+    #[test]
+    fn merges_shared_vertices() {
+        // Two triangles sharing an edge, the second one with negative indices
+        let model = parse(
+            "v 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 1 0\nvn 0 0 1\n\
+            f 1//1 2//1 3//1\n\
+            f -4//-1 -2//-1 -1//-1\n",
+        )
+        .unwrap();
+
+        let mesh = &model.meshes[0].mesh;
+        assert_eq!(mesh.vertices().len(), 4);
+        assert_eq!(mesh.indices(), &Indices::U16(vec![0, 1, 2, 0, 2, 3]));
+    }
+
+    #[test]
+    fn computes_flat_normals() {
+        // A corner of a cube: the shared vertex gets a normal per face
+        let model = parse("v 0 0 0\nv 1 0 0\nv 0 1 0\nv 0 0 1\nf 1 2 3\nf 1 2 4\n").unwrap();
+
+        let mesh = &model.meshes[0].mesh;
+        assert_eq!(mesh.vertices().len(), 6);
+        assert_eq!(mesh.vertices()[0].normal, [0.0, 0.0, 1.0]);
+        assert_eq!(mesh.vertices()[3].normal, [0.0, -1.0, 0.0]);
+    }
+
+    #[test]
+    fn splits_meshes_by_object_and_material() {
+        let mtl = "newmtl red\nKd 1 0 0\n\n# Comment\nnewmtl textured\nmap_Kd -s 2 2 2 tree.png\n";
+        let model = ObjModel::parse(
+            "mtllib scene.mtl\nv 0 0 0\nv 1 0 0\nv 0 1 0\n\
+            o first\nusemtl red\nf 1 2 3\nusemtl textured\nf 1 2 3\n\
+            o second\nf 3 2 1\n",
+            |file| {
+                assert_eq!(file, "scene.mtl");
+                Ok(mtl.to_string())
+            },
+        )
+        .unwrap();
+
+        assert_eq!(model.materials.len(), 2);
+        assert_eq!(model.materials[0].diffuse, [1.0, 0.0, 0.0]);
+        assert_eq!(
+            model.materials[1].diffuse_texture,
+            Some(PathBuf::from("tree.png"))
+        );
+
+        let meshes = model
+            .meshes
+            .iter()
+            .map(|mesh| (mesh.name.as_str(), mesh.material))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            meshes,
+            [("first", Some(0)), ("first", Some(1)), ("second", Some(1))]
+        );
+    }
+
+    #[test]
+    fn reports_malformed_lines() {
+        assert_eq!(
+            error_kind("v 0 0 0\nv 1 0 0\nf 1 2 3\n"),
+            (
+                3,
+                ObjErrorKind::IndexOutOfBounds {
+                    element: "position",
+                    index: 3,
+                    count: 2
+                }
+            )
+        );
+        assert_eq!(
+            error_kind("v 0 zero 0\n"),
+            (1, ObjErrorKind::InvalidNumber("zero".to_string()))
+        );
+        assert_eq!(
+            error_kind("v 0 0 0\nv 1 0 0\nf 1 2\n"),
+            (
+                3,
+                ObjErrorKind::MissingArguments {
+                    keyword: "f".to_string(),
+                    expected: 3,
+                    found: 2
+                }
+            )
+        );
+        assert_eq!(
+            error_kind("v 0 0 0\nf 1/1/1/1 1 1\n"),
+            (2, ObjErrorKind::InvalidFaceVertex("1/1/1/1".to_string()))
+        );
+        assert_eq!(
+            error_kind("usemtl missing\n"),
+            (1, ObjErrorKind::UnknownMaterial("missing".to_string()))
+        );
+    }
+
+    #[test]
+    fn reports_malformed_material_libraries() {
+        let result = ObjModel::parse("mtllib broken.mtl\n", |_| Ok("Kd 1 1 1\n".to_string()));
+        match result {
+            Err(ObjError::Parse { file, line, kind }) => {
+                assert_eq!(file, Some(PathBuf::from("broken.mtl")));
+                assert_eq!(line, 1);
+                assert_eq!(kind, ObjErrorKind::NoMaterial("Kd".to_string()));
+            }
+            result => panic!("Expected a parse error, got {result:?}"),
+        }
+    }
+}
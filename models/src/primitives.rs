@@ -0,0 +1,427 @@
+//! Generators for common shapes, centered on the origin with y up.
+//!
+//! Front faces wind counterclockwise, as in the examples' pipelines. Texture coordinates have
+//! their origin at the top left and tangents are computed from them, so every shape is ready for
+//! normal mapping. Flat shapes lie in the xy plane facing +z, so they can be drawn without a
+//! camera.
+
+use std::f32::consts::{PI, TAU};
+
+use crate::{
+    math::{add, cross, dot, normalize, scale, sub, Vec3},
+    Mesh, ModelVertex,
+};
+
+/// A `width` by `height` rectangle, split into `subdivisions[0]` columns and `subdivisions[1]`
+/// rows of quads.
+pub fn plane(width: f32, height: f32, subdivisions: [u32; 2]) -> Mesh<ModelVertex> {
+    let mut builder = Builder::default();
+    builder.face(
+        [0.0; 3],
+        [width, 0.0, 0.0],
+        [0.0, -height, 0.0],
+        subdivisions,
+    );
+    builder.build()
+}
+
+/// A cube with edges of length `size`, each face split into `subdivisions` by `subdivisions`
+/// quads. Every face has the full texture.
+pub fn cube(size: f32, subdivisions: u32) -> Mesh<ModelVertex> {
+    // The outward normal, and the direction of increasing u on each face
+    let faces = [
+        ([1.0, 0.0, 0.0], [0.0, 0.0, -1.0]),
+        ([-1.0, 0.0, 0.0], [0.0, 0.0, 1.0]),
+        ([0.0, 1.0, 0.0], [1.0, 0.0, 0.0]),
+        ([0.0, -1.0, 0.0], [1.0, 0.0, 0.0]),
+        ([0.0, 0.0, 1.0], [1.0, 0.0, 0.0]),
+        ([0.0, 0.0, -1.0], [-1.0, 0.0, 0.0]),
+    ];
+
+    let mut builder = Builder::default();
+    for (normal, u) in faces {
+        // Texture coordinates increase to the right and down when looking at the face
+        let v = cross(u, normal);
+        builder.face(
+            scale(normal, size / 2.0),
+            scale(u, size),
+            scale(v, size),
+            [subdivisions; 2],
+        );
+    }
+    builder.build()
+}
+
+/// A sphere made of `sectors` slices around the y axis and `stacks` rings from pole to pole.
+/// The texture wraps around it once, with its top and bottom edges at the poles.
+pub fn uv_sphere(radius: f32, sectors: u32, stacks: u32) -> Mesh<ModelVertex> {
+    assert!(
+        sectors >= 3 && stacks >= 2,
+        "A sphere needs at least 3 sectors and 2 stacks"
+    );
+    let mut builder = Builder::default();
+    builder.surface([sectors, stacks], |u, v| {
+        let normal = spherical(u * TAU, v * PI);
+        (scale(normal, radius), normal)
+    });
+    builder.build()
+}
+
+/// A sphere made by splitting the faces of an icosahedron into 4 `subdivisions` times, whose
+/// triangles are much more even than those of a [`uv_sphere`]. Texture coordinates are mapped as
+/// on a [`uv_sphere`].
+pub fn icosphere(radius: f32, subdivisions: u32) -> Mesh<ModelVertex> {
+    let t = (1.0 + 5.0f32.sqrt()) / 2.0;
+    let mut positions = [
+        [-1.0, t, 0.0],
+        [1.0, t, 0.0],
+        [-1.0, -t, 0.0],
+        [1.0, -t, 0.0],
+        [0.0, -1.0, t],
+        [0.0, 1.0, t],
+        [0.0, -1.0, -t],
+        [0.0, 1.0, -t],
+        [t, 0.0, -1.0],
+        [t, 0.0, 1.0],
+        [-t, 0.0, -1.0],
+        [-t, 0.0, 1.0],
+    ]
+    .map(|position| normalize(position).unwrap())
+    .to_vec();
+    #[rustfmt::skip]
+    let mut triangles = vec![
+        [0, 11, 5], [0, 5, 1], [0, 1, 7], [0, 7, 10], [0, 10, 11],
+        [1, 5, 9], [5, 11, 4], [11, 10, 2], [10, 7, 6], [7, 1, 8],
+        [3, 9, 4], [3, 4, 2], [3, 2, 6], [3, 6, 8], [3, 8, 9],
+        [4, 9, 5], [2, 4, 11], [6, 2, 10], [8, 6, 7], [9, 8, 1],
+    ];
+
+    for _ in 0..subdivisions {
+        let mut midpoints = std::collections::HashMap::new();
+        let mut midpoint = |a: usize, b: usize| {
+            *midpoints.entry((a.min(b), a.max(b))).or_insert_with(|| {
+                let position = normalize(add(positions[a], positions[b])).unwrap();
+                positions.push(position);
+                positions.len() - 1
+            })
+        };
+        triangles = triangles
+            .into_iter()
+            .flat_map(|[a, b, c]| {
+                let [ab, bc, ca] = [midpoint(a, b), midpoint(b, c), midpoint(c, a)];
+                [[a, ab, ca], [b, bc, ab], [c, ca, bc], [ab, bc, ca]]
+            })
+            .collect();
+    }
+
+    // Vertices are duplicated where the texture coordinates of their triangles disagree: along
+    // the seam where u wraps around, and at the poles, where u is taken from the other corners
+    let mut builder = Builder::default();
+    let mut unique = std::collections::HashMap::new();
+    for triangle in triangles {
+        let normals = triangle.map(|index| positions[index]);
+        let mut texture_coords = normals.map(|normal| {
+            let u = normal[0].atan2(normal[2]).rem_euclid(TAU) / TAU;
+            let v = normal[1].clamp(-1.0, 1.0).acos() / PI;
+            [u, v]
+        });
+        let pole = normals.map(|normal| normal[0].abs() < 1e-6 && normal[2].abs() < 1e-6);
+        let max_u = (0..3)
+            .filter(|&i| !pole[i])
+            .map(|i| texture_coords[i][0])
+            .fold(0.0, f32::max);
+        for i in (0..3).filter(|&i| !pole[i]) {
+            if max_u - texture_coords[i][0] > 0.5 {
+                texture_coords[i][0] += 1.0;
+            }
+        }
+        for i in (0..3).filter(|&i| pole[i]) {
+            let others = (0..3).filter(|&j| j != i);
+            texture_coords[i][0] = others.map(|j| texture_coords[j][0]).sum::<f32>() / 2.0;
+        }
+
+        for i in 0..3 {
+            let key = (triangle[i], texture_coords[i][0].to_bits());
+            let index = *unique.entry(key).or_insert_with(|| {
+                builder.vertex(scale(normals[i], radius), normals[i], texture_coords[i])
+            });
+            builder.indices.push(index);
+        }
+    }
+    builder.build()
+}
+
+/// A cylinder along the y axis, with `sectors` slices around it and `stacks` rings along it,
+/// closed by caps. The texture wraps around the side once, and each cap shows the whole texture.
+pub fn cylinder(radius: f32, height: f32, sectors: u32, stacks: u32) -> Mesh<ModelVertex> {
+    frustum(radius, radius, height, sectors, stacks)
+}
+
+/// A cone along the y axis with its tip at the top, with `sectors` slices around it and `stacks`
+/// rings along it, closed by a cap at the bottom. Textures are mapped as on a [`cylinder`].
+pub fn cone(radius: f32, height: f32, sectors: u32, stacks: u32) -> Mesh<ModelVertex> {
+    frustum(radius, 0.0, height, sectors, stacks)
+}
+
+fn frustum(
+    bottom_radius: f32,
+    top_radius: f32,
+    height: f32,
+    sectors: u32,
+    stacks: u32,
+) -> Mesh<ModelVertex> {
+    assert!(
+        sectors >= 3 && stacks >= 1,
+        "A cylinder or cone needs at least 3 sectors and 1 stack"
+    );
+    let mut builder = Builder::default();
+    builder.surface([sectors, stacks], |u, v| {
+        let direction = spherical(u * TAU, PI / 2.0);
+        let radius = top_radius + (bottom_radius - top_radius) * v;
+        let position = add(scale(direction, radius), [0.0, height * (0.5 - v), 0.0]);
+        // Tilted by the slope of the side
+        let normal = normalize(add(
+            scale(direction, height),
+            [0.0, bottom_radius - top_radius, 0.0],
+        ))
+        .unwrap();
+        (position, normal)
+    });
+    if top_radius > 0.0 {
+        builder.disc(height / 2.0, top_radius, sectors, 1.0);
+    }
+    if bottom_radius > 0.0 {
+        builder.disc(-height / 2.0, bottom_radius, sectors, -1.0);
+    }
+    builder.build()
+}
+
+/// A torus around the y axis, with `sectors` slices around it and `sides` around its tube. The
+/// texture wraps around it once in both directions, with its top edge on the outer equator.
+pub fn torus(major_radius: f32, minor_radius: f32, sectors: u32, sides: u32) -> Mesh<ModelVertex> {
+    assert!(
+        sectors >= 3 && sides >= 3,
+        "A torus needs at least 3 sectors and 3 sides"
+    );
+    let mut builder = Builder::default();
+    builder.surface([sectors, sides], |u, v| {
+        let direction = spherical(u * TAU, PI / 2.0);
+        let (sin, cos) = (v * TAU).sin_cos();
+        // Going down the outside of the tube first
+        let normal = add(scale(direction, cos), [0.0, -sin, 0.0]);
+        let position = add(scale(direction, major_radius), scale(normal, minor_radius));
+        (position, normal)
+    });
+    builder.build()
+}
+
+/// A regular polygon with `sides` corners at `radius` from its center, the first one at the top.
+/// The texture is mapped onto the square around it.
+pub fn polygon(sides: u32, radius: f32) -> Mesh<ModelVertex> {
+    assert!(sides >= 3, "A polygon needs at least 3 sides");
+    let mut builder = Builder::default();
+    for i in 0..sides {
+        let (sin, cos) = (PI / 2.0 + TAU * i as f32 / sides as f32).sin_cos();
+        builder.vertex(
+            [radius * cos, radius * sin, 0.0],
+            [0.0, 0.0, 1.0],
+            [0.5 + cos / 2.0, 0.5 - sin / 2.0],
+        );
+    }
+    for i in 1..sides - 1 {
+        builder.indices.extend_from_slice(&[0, i, i + 1]);
+    }
+    builder.build()
+}
+
+/// The unit vector at `azimuth` around the y axis, starting at +z and going towards +x, and
+/// `polar` down from +y.
+fn spherical(azimuth: f32, polar: f32) -> Vec3 {
+    let (sin_polar, cos_polar) = polar.sin_cos();
+    let (sin_azimuth, cos_azimuth) = azimuth.sin_cos();
+    [sin_polar * sin_azimuth, cos_polar, sin_polar * cos_azimuth]
+}
+
+#[derive(Default)]
+struct Builder {
+    vertices: Vec<ModelVertex>,
+    indices: Vec<u32>,
+}
+
+impl Builder {
+    fn vertex(&mut self, position: Vec3, normal: Vec3, texture_coords: [f32; 2]) -> u32 {
+        self.vertices.push(ModelVertex {
+            position,
+            normal,
+            texture_coords,
+            tangent: [0.0; 4],
+        });
+        self.vertices.len() as u32 - 1
+    }
+
+    /// A grid of `columns + 1` by `rows + 1` vertices at `vertex(u, v)`, with u and v going from
+    /// 0 to 1. Increasing u has to go right and increasing v down when looking at the front.
+    /// Triangles without area, e.g. at the poles of a sphere, are left out.
+    fn surface(&mut self, [columns, rows]: [u32; 2], vertex: impl Fn(f32, f32) -> (Vec3, Vec3)) {
+        assert!(
+            columns >= 1 && rows >= 1,
+            "A surface needs at least 1 subdivision"
+        );
+        let first = self.vertices.len() as u32;
+        for row in 0..=rows {
+            for column in 0..=columns {
+                let [u, v] = [column as f32 / columns as f32, row as f32 / rows as f32];
+                let (position, normal) = vertex(u, v);
+                self.vertex(position, normal, [u, v]);
+            }
+        }
+
+        let index = |column, row| first + row * (columns + 1) + column;
+        for row in 0..rows {
+            for column in 0..columns {
+                let top_left = index(column, row);
+                let top_right = index(column + 1, row);
+                let bottom_left = index(column, row + 1);
+                let bottom_right = index(column + 1, row + 1);
+                for triangle in [
+                    [top_left, bottom_left, bottom_right],
+                    [top_left, bottom_right, top_right],
+                ] {
+                    let [a, b, c] = triangle.map(|i| self.vertices[i as usize].position);
+                    let area = cross(sub(b, a), sub(c, a));
+                    if dot(area, area) > 1e-12 {
+                        self.indices.extend_from_slice(&triangle);
+                    }
+                }
+            }
+        }
+    }
+
+    /// A flat rectangular face centered on `center`, spanning `u` to the right and `v` down.
+    fn face(&mut self, center: Vec3, u: Vec3, v: Vec3, subdivisions: [u32; 2]) {
+        let normal = normalize(cross(v, u)).unwrap();
+        self.surface(subdivisions, |s, t| {
+            let position = add(center, add(scale(u, s - 0.5), scale(v, t - 0.5)));
+            (position, normal)
+        });
+    }
+
+    /// A disc in the xz plane at height `y`, facing up for `facing` 1 or down for -1.
+    fn disc(&mut self, y: f32, radius: f32, sectors: u32, facing: f32) {
+        let normal = [0.0, facing, 0.0];
+        let center = self.vertex([0.0, y, 0.0], normal, [0.5, 0.5]);
+        for i in 0..sectors {
+            let direction = spherical(TAU * i as f32 / sectors as f32, PI / 2.0);
+            self.vertex(
+                add(scale(direction, radius), [0.0, y, 0.0]),
+                normal,
+                [0.5 + direction[0] / 2.0, 0.5 + facing * direction[2] / 2.0],
+            );
+        }
+        for i in 0..sectors {
+            let [a, b] = [center + 1 + i, center + 1 + (i + 1) % sectors];
+            let triangle = if facing > 0.0 {
+                [center, a, b]
+            } else {
+                [center, b, a]
+            };
+            self.indices.extend_from_slice(&triangle);
+        }
+    }
+
+    fn build(self) -> Mesh<ModelVertex> {
+        let mut mesh = Mesh::new(self.vertices, self.indices);
+        mesh.compute_tangents();
+        mesh
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn all() -> Vec<(&'static str, Mesh<ModelVertex>)> {
+        vec![
+            ("plane", plane(2.0, 1.0, [3, 2])),
+            ("cube", cube(1.0, 2)),
+            ("uv_sphere", uv_sphere(1.0, 16, 8)),
+            ("icosphere", icosphere(1.0, 2)),
+            ("cylinder", cylinder(0.5, 2.0, 12, 3)),
+            ("cone", cone(0.5, 1.0, 12, 2)),
+            ("torus", torus(1.0, 0.25, 16, 8)),
+            ("polygon", polygon(5, 0.5)),
+        ]
+    }
+
+    #[test]
+    fn triangles_face_their_normals() {
+        for (name, mesh) in all() {
+            let indices = mesh.indices().iter().collect::<Vec<_>>();
+            assert!(!indices.is_empty(), "{name} has no triangles");
+            for triangle in indices.chunks_exact(3) {
+                let [a, b, c] = [0, 1, 2].map(|i| mesh.vertices()[triangle[i] as usize]);
+                let face_normal = cross(sub(b.position, a.position), sub(c.position, a.position));
+                for vertex in [a, b, c] {
+                    assert!(
+                        dot(face_normal, vertex.normal) > 0.0,
+                        "{name}: triangle {triangle:?} winds against its normals"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn closed_shapes_face_outwards() {
+        for (name, mesh) in all().into_iter().filter(|(name, _)| *name != "torus") {
+            for vertex in mesh.vertices() {
+                assert!(
+                    dot(vertex.position, vertex.normal) >= 0.0,
+                    "{name}: {vertex:?} faces inwards"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn tangents_are_perpendicular_unit_vectors() {
+        for (name, mesh) in all() {
+            for vertex in mesh.vertices() {
+                let tangent = [vertex.tangent[0], vertex.tangent[1], vertex.tangent[2]];
+                assert!(
+                    (dot(vertex.normal, vertex.normal) - 1.0).abs() < 1e-5,
+                    "{name}"
+                );
+                assert!(
+                    (dot(tangent, tangent) - 1.0).abs() < 1e-5,
+                    "{name}: {vertex:?}"
+                );
+                assert!(
+                    dot(tangent, vertex.normal).abs() < 1e-5,
+                    "{name}: {vertex:?}"
+                );
+                assert_eq!(vertex.tangent[3].abs(), 1.0, "{name}");
+            }
+        }
+    }
+
+    #[test]
+    fn icosphere_vertices_lie_on_the_sphere() {
+        let mesh = icosphere(2.0, 3);
+        // 20 * 4^3 triangles
+        assert_eq!(mesh.indices().len(), 1280 * 3);
+        for vertex in mesh.vertices() {
+            assert!((dot(vertex.position, vertex.position).sqrt() - 2.0).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn polygon_matches_its_radius() {
+        let mesh = polygon(6, 0.5);
+        assert_eq!(mesh.vertices().len(), 6);
+        assert_eq!(mesh.indices().len(), 4 * 3);
+        let top = mesh.vertices()[0];
+        assert!(top.position[0].abs() < 1e-6 && top.position[1] == 0.5);
+        assert!((top.texture_coords[0] - 0.5).abs() < 1e-6 && top.texture_coords[1] == 0.0);
+    }
+}
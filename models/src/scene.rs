@@ -0,0 +1,697 @@
+use std::{fmt, path::Path};
+
+use gltf::{image::Format, mesh::Mode, texture::WrappingMode};
+use image::{DynamicImage, ImageBuffer};
+
+use crate::{
+    math::{cross, mul, normalize, sub, IDENTITY},
+    Mesh, ModelVertex, Texture,
+};
+
+#[derive(Debug)]
+pub enum SceneError {
+    /// The file, its buffers or its images could not be read.
+    Gltf(gltf::Error),
+    /// A primitive of a mesh has no vertex positions.
+    MissingPositions { mesh: usize },
+    /// A primitive of a mesh is made of points, lines or strips instead of a triangle list.
+    UnsupportedMode { mesh: usize, mode: Mode },
+    /// A primitive of a mesh has an attribute with a different number of values than positions.
+    AttributeCount {
+        mesh: usize,
+        attribute: &'static str,
+        count: usize,
+        positions: usize,
+    },
+    /// A primitive of a mesh has an index past its vertices.
+    IndexOutOfBounds {
+        mesh: usize,
+        index: u32,
+        vertices: usize,
+    },
+    /// An image has a pixel layout that doesn't match its format.
+    InvalidImage { image: usize },
+}
+
+impl fmt::Display for SceneError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SceneError::Gltf(e) => write!(f, "Error importing glTF: {e}"),
+            SceneError::MissingPositions { mesh } => {
+                write!(f, "A primitive of mesh {mesh} has no positions")
+            }
+            SceneError::UnsupportedMode { mesh, mode } => write!(
+                f,
+                "A primitive of mesh {mesh} is drawn as {mode:?}, only triangles are supported"
+            ),
+            SceneError::AttributeCount {
+                mesh,
+                attribute,
+                count,
+                positions,
+            } => write!(
+                f,
+                "A primitive of mesh {mesh} has {count} {attribute} values for {positions} positions"
+            ),
+            SceneError::IndexOutOfBounds {
+                mesh,
+                index,
+                vertices,
+            } => write!(
+                f,
+                "A primitive of mesh {mesh} has index {index}, but only {vertices} vertices"
+            ),
+            SceneError::InvalidImage { image } => {
+                write!(f, "The pixels of image {image} don't match its format")
+            }
+        }
+    }
+}
+
+impl std::error::Error for SceneError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SceneError::Gltf(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<gltf::Error> for SceneError {
+    fn from(e: gltf::Error) -> Self {
+        SceneError::Gltf(e)
+    }
+}
+
+/// A metallic-roughness material. Textures are indices into [`Scene::textures`], and are
+/// multiplied with their factors.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Material {
+    pub name: Option<String>,
+    pub base_color_factor: [f32; 4],
+    /// sRGB.
+    pub base_color_texture: Option<usize>,
+    pub metallic_factor: f32,
+    pub roughness_factor: f32,
+    /// Linear, with roughness in the green and metalness in the blue channel.
+    pub metallic_roughness_texture: Option<usize>,
+    /// Linear, in tangent space.
+    pub normal_texture: Option<usize>,
+    pub normal_scale: f32,
+}
+
+/// Part of a mesh drawn with one material.
+#[derive(Clone, Debug)]
+pub struct Primitive {
+    pub mesh: Mesh<ModelVertex>,
+    /// Index into [`Scene::materials`], `None` for the glTF default material.
+    pub material: Option<usize>,
+}
+
+#[derive(Clone, Debug)]
+pub struct SceneMesh {
+    pub name: Option<String>,
+    pub primitives: Vec<Primitive>,
+}
+
+/// A node of the scene hierarchy.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Node {
+    pub name: Option<String>,
+    /// Relative to the parent node, column major.
+    pub transform: [[f32; 4]; 4],
+    /// Index into [`Scene::meshes`].
+    pub mesh: Option<usize>,
+    /// Indices into [`Scene::nodes`].
+    pub children: Vec<usize>,
+}
+
+/// The meshes, materials, textures and node hierarchy of a glTF 2.0 file.
+///
+/// Meshes, nodes, materials and textures keep their glTF indices, textures used both as base
+/// colors and as data are followed by linear copies for the latter. Primitives without normals
+/// get flat ones, and primitives without tangents get them computed from their first set of
+/// texture coordinates, which is the only one imported.
+pub struct Scene {
+    pub meshes: Vec<SceneMesh>,
+    pub materials: Vec<Material>,
+    pub textures: Vec<Texture>,
+    pub nodes: Vec<Node>,
+    /// Indices into [`Scene::nodes`] of the nodes in the file's default scene.
+    pub roots: Vec<usize>,
+}
+
+impl Scene {
+    /// Load a `.gltf` file with its external buffers and images, or a `.glb` file.
+    pub fn load(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        path: impl AsRef<Path>,
+    ) -> Result<Self, SceneError> {
+        let (document, buffers, images) = gltf::import(path)?;
+        Self::from_gltf(device, queue, &document, &buffers, &images)
+    }
+
+    /// Load a `.glb` file, or a `.gltf` file with all buffers and images embedded, from memory.
+    pub fn from_slice(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        bytes: &[u8],
+    ) -> Result<Self, SceneError> {
+        let (document, buffers, images) = gltf::import_slice(bytes)?;
+        Self::from_gltf(device, queue, &document, &buffers, &images)
+    }
+
+    /// The transform of every node relative to the scene, i.e. combined with those of its
+    /// ancestors. Nodes outside of the default scene keep their own transform.
+    pub fn world_transforms(&self) -> Vec<[[f32; 4]; 4]> {
+        let mut transforms = self
+            .nodes
+            .iter()
+            .map(|node| node.transform)
+            .collect::<Vec<_>>();
+        let mut stack = self
+            .roots
+            .iter()
+            .map(|&root| (root, IDENTITY))
+            .collect::<Vec<_>>();
+        while let Some((node, parent)) = stack.pop() {
+            transforms[node] = mul(&parent, &self.nodes[node].transform);
+            for &child in &self.nodes[node].children {
+                stack.push((child, transforms[node]));
+            }
+        }
+        transforms
+    }
+
+    fn from_gltf(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        document: &gltf::Document,
+        buffers: &[gltf::buffer::Data],
+        images: &[gltf::image::Data],
+    ) -> Result<Self, SceneError> {
+        let Geometry {
+            meshes,
+            mut materials,
+            nodes,
+            roots,
+        } = import_geometry(document, buffers)?;
+
+        let gltf_textures = document.textures().collect::<Vec<_>>();
+        let textures = texture_color_spaces(&mut materials, gltf_textures.len())
+            .into_iter()
+            .enumerate()
+            .map(|(index, (texture, srgb))| {
+                let texture = &gltf_textures[texture];
+                let image = texture.source().index();
+                let mut label = texture.name().map_or_else(
+                    || format!("glTF texture {}", texture.index()),
+                    str::to_string,
+                );
+                if index >= gltf_textures.len() {
+                    label.push_str(" (linear)");
+                }
+                let format = if srgb {
+                    wgpu::TextureFormat::Rgba8UnormSrgb
+                } else {
+                    wgpu::TextureFormat::Rgba8Unorm
+                };
+                let mut created = Texture::from_image(
+                    device,
+                    queue,
+                    &to_dynamic_image(&images[image]).ok_or(SceneError::InvalidImage { image })?,
+                    &label,
+                    format,
+                );
+                created.sampler = create_sampler(device, &texture.sampler());
+                Ok(created)
+            })
+            .collect::<Result<Vec<_>, SceneError>>()?;
+
+        Ok(Self {
+            meshes,
+            materials,
+            textures,
+            nodes,
+            roots,
+        })
+    }
+}
+
+/// The glTF texture and whether it is sRGB, for every texture to create. Only base colors are
+/// sRGB, everything else is data, so textures used as both get a linear copy after the glTF
+/// textures, which the materials' data textures are pointed at instead.
+fn texture_color_spaces(materials: &mut [Material], textures: usize) -> Vec<(usize, bool)> {
+    let mut srgb = vec![false; textures];
+    for material in materials.iter() {
+        if let Some(texture) = material.base_color_texture {
+            srgb[texture] = true;
+        }
+    }
+
+    let mut created = srgb.iter().copied().enumerate().collect::<Vec<_>>();
+    let mut linear_copies = vec![None; textures];
+    for material in materials {
+        let data_textures = [
+            &mut material.metallic_roughness_texture,
+            &mut material.normal_texture,
+        ];
+        for texture in data_textures.into_iter().flatten() {
+            let original = *texture;
+            if srgb[original] {
+                *texture = *linear_copies[original].get_or_insert_with(|| {
+                    created.push((original, false));
+                    created.len() - 1
+                });
+            }
+        }
+    }
+    created
+}
+
+/// Everything of a scene but its textures, which need a device.
+struct Geometry {
+    meshes: Vec<SceneMesh>,
+    materials: Vec<Material>,
+    nodes: Vec<Node>,
+    roots: Vec<usize>,
+}
+
+fn import_geometry(
+    document: &gltf::Document,
+    buffers: &[gltf::buffer::Data],
+) -> Result<Geometry, SceneError> {
+    let meshes = document
+        .meshes()
+        .map(|mesh| {
+            let primitives = mesh
+                .primitives()
+                .map(|primitive| import_primitive(mesh.index(), &primitive, buffers))
+                .collect::<Result<_, _>>()?;
+            Ok(SceneMesh {
+                name: mesh.name().map(str::to_string),
+                primitives,
+            })
+        })
+        .collect::<Result<_, SceneError>>()?;
+
+    let materials = document
+        .materials()
+        .map(|material| {
+            let pbr = material.pbr_metallic_roughness();
+            Material {
+                name: material.name().map(str::to_string),
+                base_color_factor: pbr.base_color_factor(),
+                base_color_texture: pbr.base_color_texture().map(|info| info.texture().index()),
+                metallic_factor: pbr.metallic_factor(),
+                roughness_factor: pbr.roughness_factor(),
+                metallic_roughness_texture: pbr
+                    .metallic_roughness_texture()
+                    .map(|info| info.texture().index()),
+                normal_texture: material
+                    .normal_texture()
+                    .map(|normal| normal.texture().index()),
+                normal_scale: material
+                    .normal_texture()
+                    .map_or(1.0, |normal| normal.scale()),
+            }
+        })
+        .collect();
+
+    let nodes = document
+        .nodes()
+        .map(|node| Node {
+            name: node.name().map(str::to_string),
+            transform: node.transform().matrix(),
+            mesh: node.mesh().map(|mesh| mesh.index()),
+            children: node.children().map(|child| child.index()).collect(),
+        })
+        .collect();
+
+    let roots = document
+        .default_scene()
+        .or_else(|| document.scenes().next())
+        .map_or_else(Vec::new, |scene| {
+            scene.nodes().map(|node| node.index()).collect()
+        });
+
+    Ok(Geometry {
+        meshes,
+        materials,
+        nodes,
+        roots,
+    })
+}
+
+fn import_primitive(
+    mesh: usize,
+    primitive: &gltf::Primitive,
+    buffers: &[gltf::buffer::Data],
+) -> Result<Primitive, SceneError> {
+    if primitive.mode() != Mode::Triangles {
+        return Err(SceneError::UnsupportedMode {
+            mesh,
+            mode: primitive.mode(),
+        });
+    }
+    let reader = primitive.reader(|buffer| Some(&buffers[buffer.index()]));
+
+    let mut vertices = reader
+        .read_positions()
+        .ok_or(SceneError::MissingPositions { mesh })?
+        .map(|position| ModelVertex {
+            position,
+            normal: [0.0; 3],
+            texture_coords: [0.0; 2],
+            tangent: [0.0; 4],
+        })
+        .collect::<Vec<_>>();
+    let texture_coords = per_vertex(
+        mesh,
+        "TEXCOORD_0",
+        reader
+            .read_tex_coords(0)
+            .map(|texture_coords| texture_coords.into_f32()),
+        vertices.len(),
+    )?;
+    let normals = per_vertex(mesh, "NORMAL", reader.read_normals(), vertices.len())?;
+    let tangents = per_vertex(mesh, "TANGENT", reader.read_tangents(), vertices.len())?;
+
+    if let Some(texture_coords) = texture_coords {
+        for (vertex, texture_coords) in vertices.iter_mut().zip(texture_coords) {
+            vertex.texture_coords = texture_coords;
+        }
+    }
+    let mut indices = match reader.read_indices() {
+        Some(indices) => indices.into_u32().collect(),
+        None => (0..vertices.len() as u32).collect::<Vec<_>>(),
+    };
+    if let Some(&index) = indices
+        .iter()
+        .find(|&&index| index as usize >= vertices.len())
+    {
+        return Err(SceneError::IndexOutOfBounds {
+            mesh,
+            index,
+            vertices: vertices.len(),
+        });
+    }
+
+    let has_normals = normals.is_some();
+    match normals {
+        Some(normals) => {
+            for (vertex, normal) in vertices.iter_mut().zip(normals) {
+                vertex.normal = normal;
+            }
+        }
+        None => (vertices, indices) = flat_normals(&vertices, &indices),
+    }
+
+    let mut mesh = Mesh::new(vertices, indices);
+    // Given tangents only make sense with the given normals
+    match tangents.filter(|_| has_normals) {
+        Some(tangents) => {
+            for (vertex, tangent) in mesh.vertices.iter_mut().zip(tangents) {
+                vertex.tangent = tangent;
+            }
+        }
+        None => mesh.compute_tangents(),
+    }
+
+    Ok(Primitive {
+        mesh,
+        material: primitive.material().index(),
+    })
+}
+
+/// Collect the values of an attribute, which needs one for every position.
+fn per_vertex<T>(
+    mesh: usize,
+    attribute: &'static str,
+    values: Option<impl Iterator<Item = T>>,
+    positions: usize,
+) -> Result<Option<Vec<T>>, SceneError> {
+    let Some(values) = values else {
+        return Ok(None);
+    };
+    let values = values.collect::<Vec<_>>();
+    if values.len() != positions {
+        return Err(SceneError::AttributeCount {
+            mesh,
+            attribute,
+            count: values.len(),
+            positions,
+        });
+    }
+    Ok(Some(values))
+}
+
+/// Give every triangle its own vertices, with the triangle's normal.
+fn flat_normals(vertices: &[ModelVertex], indices: &[u32]) -> (Vec<ModelVertex>, Vec<u32>) {
+    let vertices = indices
+        .chunks_exact(3)
+        .flat_map(|triangle| {
+            let corners = [0, 1, 2].map(|i| vertices[triangle[i] as usize]);
+            let [a, b, c] = corners.map(|vertex| vertex.position);
+            let normal = normalize(cross(sub(b, a), sub(c, a))).unwrap_or([0.0, 0.0, 1.0]);
+            corners.map(|vertex| ModelVertex { normal, ..vertex })
+        })
+        .collect::<Vec<_>>();
+    let indices = (0..vertices.len() as u32).collect();
+    (vertices, indices)
+}
+
+fn to_dynamic_image(image: &gltf::image::Data) -> Option<DynamicImage> {
+    let (width, height) = (image.width, image.height);
+    let pixels = image.pixels.clone();
+    // 16 and 32 bit channels are stored in native byte order
+    let u16s = || {
+        image
+            .pixels
+            .chunks_exact(2)
+            .map(|c| u16::from_ne_bytes([c[0], c[1]]))
+            .collect::<Vec<_>>()
+    };
+    let f32s = || {
+        image
+            .pixels
+            .chunks_exact(4)
+            .map(|c| f32::from_ne_bytes([c[0], c[1], c[2], c[3]]))
+            .collect::<Vec<_>>()
+    };
+    Some(match image.format {
+        Format::R8 => DynamicImage::ImageLuma8(ImageBuffer::from_raw(width, height, pixels)?),
+        Format::R8G8 => DynamicImage::ImageLumaA8(ImageBuffer::from_raw(width, height, pixels)?),
+        Format::R8G8B8 => DynamicImage::ImageRgb8(ImageBuffer::from_raw(width, height, pixels)?),
+        Format::R8G8B8A8 => DynamicImage::ImageRgba8(ImageBuffer::from_raw(width, height, pixels)?),
+        Format::R16 => DynamicImage::ImageLuma16(ImageBuffer::from_raw(width, height, u16s())?),
+        Format::R16G16 => DynamicImage::ImageLumaA16(ImageBuffer::from_raw(width, height, u16s())?),
+        Format::R16G16B16 => {
+            DynamicImage::ImageRgb16(ImageBuffer::from_raw(width, height, u16s())?)
+        }
+        Format::R16G16B16A16 => {
+            DynamicImage::ImageRgba16(ImageBuffer::from_raw(width, height, u16s())?)
+        }
+        Format::R32G32B32FLOAT => {
+            DynamicImage::ImageRgb32F(ImageBuffer::from_raw(width, height, f32s())?)
+        }
+        Format::R32G32B32A32FLOAT => {
+            DynamicImage::ImageRgba32F(ImageBuffer::from_raw(width, height, f32s())?)
+        }
+    })
+}
+
+fn create_sampler(device: &wgpu::Device, sampler: &gltf::texture::Sampler) -> wgpu::Sampler {
+    use gltf::texture::{MagFilter, MinFilter};
+
+    let address_mode = |mode| match mode {
+        WrappingMode::ClampToEdge => wgpu::AddressMode::ClampToEdge,
+        WrappingMode::MirroredRepeat => wgpu::AddressMode::MirrorRepeat,
+        WrappingMode::Repeat => wgpu::AddressMode::Repeat,
+    };
+    let mag_filter = match sampler.mag_filter() {
+        Some(MagFilter::Nearest) => wgpu::FilterMode::Nearest,
+        Some(MagFilter::Linear) | None => wgpu::FilterMode::Linear,
+    };
+    let (min_filter, mipmap_filter) = match sampler.min_filter() {
+        Some(MinFilter::Nearest | MinFilter::NearestMipmapNearest) => {
+            (wgpu::FilterMode::Nearest, wgpu::FilterMode::Nearest)
+        }
+        Some(MinFilter::NearestMipmapLinear) => {
+            (wgpu::FilterMode::Nearest, wgpu::FilterMode::Linear)
+        }
+        Some(MinFilter::Linear | MinFilter::LinearMipmapNearest) | None => {
+            (wgpu::FilterMode::Linear, wgpu::FilterMode::Nearest)
+        }
+        Some(MinFilter::LinearMipmapLinear) => (wgpu::FilterMode::Linear, wgpu::FilterMode::Linear),
+    };
+
+    device.create_sampler(&wgpu::SamplerDescriptor {
+        label: sampler.name(),
+        address_mode_u: address_mode(sampler.wrap_s()),
+        address_mode_v: address_mode(sampler.wrap_t()),
+        mag_filter,
+        min_filter,
+        mipmap_filter,
+        ..Default::default()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Indices;
+
+    /// A triangle without normals in a node hierarchy, with its buffer embedded as a data URI.
+    const SCENE: &str = r#"{
+        "asset": { "version": "2.0" },
+        "scene": 0,
+        "scenes": [{ "nodes": [0] }],
+        "nodes": [
+            { "name": "parent", "translation": [1, 0, 0], "children": [1] },
+            { "name": "child", "translation": [0, 2, 0], "scale": [2, 2, 2], "mesh": 0 }
+        ],
+        "meshes": [{
+            "name": "triangle",
+            "primitives": [{ "attributes": { "POSITION": 0, "TEXCOORD_0": 1 } }]
+        }],
+        "accessors": [
+            { "bufferView": 0, "componentType": 5126, "count": 3, "type": "VEC3",
+              "min": [0, 0, 0], "max": [1, 1, 0] },
+            { "bufferView": 1, "componentType": 5126, "count": 3, "type": "VEC2" }
+        ],
+        "bufferViews": [
+            { "buffer": 0, "byteOffset": 0, "byteLength": 36 },
+            { "buffer": 0, "byteOffset": 36, "byteLength": 24 }
+        ],
+        "buffers": [{ "byteLength": 60, "uri": "data:application/octet-stream;base64,AAAAAAAAAAAAAAAAAACAPwAAAAAAAAAAAAAAAAAAgD8AAAAAAAAAAAAAAAAAAIA/AAAAAAAAAAAAAIA/" }]
+    }"#;
+
+    fn import_str(gltf: &str) -> Result<Geometry, SceneError> {
+        let (document, buffers, _) = gltf::import_slice(gltf)?;
+        import_geometry(&document, &buffers)
+    }
+
+    fn import() -> Geometry {
+        import_str(SCENE).unwrap()
+    }
+
+    fn material(base_color: usize, normal: usize) -> Material {
+        Material {
+            name: None,
+            base_color_factor: [1.0; 4],
+            base_color_texture: Some(base_color),
+            metallic_factor: 1.0,
+            roughness_factor: 1.0,
+            metallic_roughness_texture: None,
+            normal_texture: Some(normal),
+            normal_scale: 1.0,
+        }
+    }
+
+    #[test]
+    fn imports_meshes() {
+        let geometry = import();
+        assert_eq!(geometry.meshes.len(), 1);
+        assert_eq!(geometry.meshes[0].name.as_deref(), Some("triangle"));
+
+        let primitive = &geometry.meshes[0].primitives[0];
+        assert_eq!(primitive.material, None);
+        assert_eq!(primitive.mesh.indices(), &Indices::U16(vec![0, 1, 2]));
+        for vertex in primitive.mesh.vertices() {
+            assert_eq!(vertex.normal, [0.0, 0.0, 1.0]);
+            assert_eq!(vertex.tangent, [1.0, 0.0, 0.0, 1.0]);
+        }
+    }
+
+    #[test]
+    fn combines_node_transforms() {
+        let geometry = import();
+        assert_eq!(geometry.roots, [0]);
+        assert_eq!(geometry.nodes[0].children, [1]);
+        assert_eq!(geometry.nodes[1].mesh, Some(0));
+
+        let scene = Scene {
+            meshes: geometry.meshes,
+            materials: geometry.materials,
+            textures: vec![],
+            nodes: geometry.nodes,
+            roots: geometry.roots,
+        };
+        let transforms = scene.world_transforms();
+        assert_eq!(transforms[0][3], [1.0, 0.0, 0.0, 1.0]);
+        assert_eq!(
+            transforms[1],
+            [
+                [2.0, 0.0, 0.0, 0.0],
+                [0.0, 2.0, 0.0, 0.0],
+                [0.0, 0.0, 2.0, 0.0],
+                [1.0, 2.0, 0.0, 1.0],
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_attributes_without_a_value_per_position() {
+        let scene = SCENE.replace(
+            r#""count": 3, "type": "VEC2""#,
+            r#""count": 2, "type": "VEC2""#,
+        );
+        assert!(matches!(
+            import_str(&scene),
+            Err(SceneError::AttributeCount {
+                mesh: 0,
+                attribute: "TEXCOORD_0",
+                count: 2,
+                positions: 3,
+            })
+        ));
+    }
+
+    #[test]
+    fn rejects_indices_past_the_vertices() {
+        // Reads the positions' bits as indices, the first of which is the bits of 1.0
+        let scene = SCENE
+            .replace(r#""TEXCOORD_0": 1 }"#, r#""TEXCOORD_0": 1 }, "indices": 2"#)
+            .replace(
+                r#""count": 3, "type": "VEC2" }"#,
+                r#""count": 3, "type": "VEC2" },
+            { "bufferView": 0, "byteOffset": 12, "componentType": 5125, "count": 3,
+              "type": "SCALAR" }"#,
+            );
+        assert!(matches!(
+            import_str(&scene),
+            Err(SceneError::IndexOutOfBounds {
+                mesh: 0,
+                index: 0x3f80_0000,
+                vertices: 3,
+            })
+        ));
+    }
+
+    #[test]
+    fn only_base_colors_are_srgb() {
+        let mut materials = [material(0, 1), material(2, 1)];
+        assert_eq!(
+            texture_color_spaces(&mut materials, 4),
+            [(0, true), (1, false), (2, true), (3, false)]
+        );
+        assert_eq!(materials[0].normal_texture, Some(1));
+        assert_eq!(materials[1].normal_texture, Some(1));
+    }
+
+    #[test]
+    fn textures_used_as_colors_and_data_get_a_linear_copy() {
+        let mut materials = [material(0, 1), material(1, 2), material(3, 1)];
+        assert_eq!(
+            texture_color_spaces(&mut materials, 4),
+            [(0, true), (1, true), (2, false), (3, true), (1, false)]
+        );
+        // Both materials share the one copy
+        assert_eq!(materials[0].normal_texture, Some(4));
+        assert_eq!(materials[1].base_color_texture, Some(1));
+        assert_eq!(materials[1].normal_texture, Some(2));
+        assert_eq!(materials[2].normal_texture, Some(4));
+    }
+}
@@ -15,8 +15,33 @@ impl Texture {
     ) -> Result<Texture, ImageError> {
         // Load image
         let diffuse_image = image::load_from_memory(bytes)?;
-        let diffuse_rgba = diffuse_image.to_rgba8();
-        let dimensions = diffuse_image.dimensions();
+        Ok(Self::from_image(
+            device,
+            queue,
+            &diffuse_image,
+            label,
+            wgpu::TextureFormat::Rgba8UnormSrgb,
+        ))
+    }
+
+    /// Create a texture from an image already in memory. `format` is `Rgba8UnormSrgb` for colors,
+    /// or `Rgba8Unorm` for data like normal maps, which must not be converted from sRGB.
+    pub fn from_image(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        image: &image::DynamicImage,
+        label: &str,
+        format: wgpu::TextureFormat,
+    ) -> Texture {
+        assert!(
+            matches!(
+                format,
+                wgpu::TextureFormat::Rgba8UnormSrgb | wgpu::TextureFormat::Rgba8Unorm
+            ),
+            "Textures are created from RGBA images, {format:?} is not supported"
+        );
+        let diffuse_rgba = image.to_rgba8();
+        let dimensions = image.dimensions();
 
         // Create the texture
         let texture_size = wgpu::Extent3d {
@@ -31,8 +56,8 @@ impl Texture {
             mip_level_count: 1, // We'll talk about this a little later
             sample_count: 1,
             dimension: wgpu::TextureDimension::D2,
-            // Most images are stored using sRGB so we need to reflect that here.
-            format: wgpu::TextureFormat::Rgba8UnormSrgb,
+            // Most images are stored using sRGB, data like normal maps is linear
+            format,
             // TEXTURE_BINDING tells wgpu that we want to use this texture in shaders
             // COPY_DST means that we want to copy data to this texture
             usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
@@ -80,6 +105,6 @@ impl Texture {
             ..Default::default()
         });
 
-        Ok(Self { texture, view, sampler })
+        Self { texture, view, sampler }
     } 
 }
\ No newline at end of file
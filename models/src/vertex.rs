@@ -1,56 +1,130 @@
+/// A vertex type that can be read from a vertex buffer, usually implemented with
+/// `#[derive(Vertex)]`.
+pub trait Vertex: bytemuck::Pod {
+    /// The layout of a vertex buffer holding this type.
+    fn desc<'a>() -> wgpu::VertexBufferLayout<'a>;
+}
 
 #[repr(C)]
-#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable, models_derive::Vertex)]
 pub struct ColoredVertex {
     pub position: [f32; 3],
     pub color: [f32; 3],
 }
 
-impl ColoredVertex {
-    pub fn desc<'a>() -> wgpu::VertexBufferLayout<'a> {
-        wgpu::VertexBufferLayout {
-            array_stride: std::mem::size_of::<ColoredVertex>() as wgpu::BufferAddress,
-            step_mode: wgpu::VertexStepMode::Vertex,
-            attributes: &[
-                wgpu::VertexAttribute {
-                    offset: 0,
-                    shader_location: 0,
-                    format: wgpu::VertexFormat::Float32x3,
-                },
-                wgpu::VertexAttribute {
-                    offset: std::mem::size_of::<[f32; 3]>() as wgpu::BufferAddress,
-                    shader_location: 1,
-                    format: wgpu::VertexFormat::Float32x3,
-                }
-            ]
-        }
-    }
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable, models_derive::Vertex)]
+pub struct TexturedVertex {
+    pub position: [f32; 3],
+    pub texture_coords: [f32; 2],
 }
 
+/// A vertex of a lit, textured model, e.g. loaded from an OBJ file.
 #[repr(C)]
-#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
-pub struct TexturedVertex {
+#[derive(
+    Copy, Clone, Debug, PartialEq, bytemuck::Pod, bytemuck::Zeroable, models_derive::Vertex,
+)]
+pub struct ModelVertex {
     pub position: [f32; 3],
+    pub normal: [f32; 3],
     pub texture_coords: [f32; 2],
+    /// Direction of increasing u in model space, with the sign of the bitangent in `w` as in
+    /// glTF: `bitangent = cross(normal, tangent.xyz) * tangent.w`.
+    pub tangent: [f32; 4],
 }
 
-impl TexturedVertex {
-    pub fn desc<'a>() -> wgpu::VertexBufferLayout<'a> {
-        wgpu::VertexBufferLayout {
-            array_stride: std::mem::size_of::<TexturedVertex>() as wgpu::BufferAddress,
-            step_mode: wgpu::VertexStepMode::Vertex,
-            attributes: &[
-                wgpu::VertexAttribute {
-                    offset: 0,
-                    shader_location: 0,
-                    format: wgpu::VertexFormat::Float32x3,
-                },
-                wgpu::VertexAttribute {
-                    offset: std::mem::size_of::<[f32; 3]>() as wgpu::BufferAddress,
-                    shader_location: 1,
-                    format: wgpu::VertexFormat::Float32x2,
-                }
-            ]
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn derived_layouts_match_hand_written_ones() {
+        assert_eq!(
+            ColoredVertex::desc(),
+            wgpu::VertexBufferLayout {
+                array_stride: 24,
+                step_mode: wgpu::VertexStepMode::Vertex,
+                attributes: &[
+                    wgpu::VertexAttribute {
+                        offset: 0,
+                        shader_location: 0,
+                        format: wgpu::VertexFormat::Float32x3,
+                    },
+                    wgpu::VertexAttribute {
+                        offset: 12,
+                        shader_location: 1,
+                        format: wgpu::VertexFormat::Float32x3,
+                    },
+                ],
+            }
+        );
+        assert_eq!(
+            TexturedVertex::desc(),
+            wgpu::VertexBufferLayout {
+                array_stride: 20,
+                step_mode: wgpu::VertexStepMode::Vertex,
+                attributes: &[
+                    wgpu::VertexAttribute {
+                        offset: 0,
+                        shader_location: 0,
+                        format: wgpu::VertexFormat::Float32x3,
+                    },
+                    wgpu::VertexAttribute {
+                        offset: 12,
+                        shader_location: 1,
+                        format: wgpu::VertexFormat::Float32x2,
+                    },
+                ],
+            }
+        );
+    }
+
+    #[repr(C)]
+    #[derive(Copy, Clone, bytemuck::Pod, bytemuck::Zeroable, crate::Vertex)]
+    #[vertex(step_mode = "instance")]
+    struct Instance {
+        #[vertex(location = 5)]
+        offset: [f32; 3],
+        #[vertex(skip)]
+        _padding: u32,
+        // Aligned to 8 bytes
+        scale: [f64; 2],
+        #[vertex(format = "Unorm8x4")]
+        color: [u8; 4],
+        #[vertex(location = 2)]
+        id: u32,
+    }
+
+    #[test]
+    fn attribute_overrides() {
+        assert_eq!(
+            Instance::desc(),
+            wgpu::VertexBufferLayout {
+                array_stride: 40,
+                step_mode: wgpu::VertexStepMode::Instance,
+                attributes: &[
+                    wgpu::VertexAttribute {
+                        offset: 0,
+                        shader_location: 5,
+                        format: wgpu::VertexFormat::Float32x3,
+                    },
+                    wgpu::VertexAttribute {
+                        offset: 16,
+                        shader_location: 6,
+                        format: wgpu::VertexFormat::Float64x2,
+                    },
+                    wgpu::VertexAttribute {
+                        offset: 32,
+                        shader_location: 7,
+                        format: wgpu::VertexFormat::Unorm8x4,
+                    },
+                    wgpu::VertexAttribute {
+                        offset: 36,
+                        shader_location: 2,
+                        format: wgpu::VertexFormat::Uint32,
+                    },
+                ],
+            }
+        );
     }
 }
@@ -0,0 +1,9 @@
+#[repr(C)]
+#[derive(Clone, Copy, models::Vertex)]
+struct Vertex {
+    position: [f32; 3],
+    #[vertex(location = 0)]
+    normal: [f32; 3],
+}
+
+fn main() {}
@@ -0,0 +1,7 @@
+#[derive(Clone, Copy, models::Vertex)]
+struct Vertex {
+    position: [f32; 3],
+    normal: [f32; 3],
+}
+
+fn main() {}
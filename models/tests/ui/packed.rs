@@ -0,0 +1,8 @@
+#[repr(C, packed)]
+#[derive(Clone, Copy, models::Vertex)]
+struct Vertex {
+    position: [f32; 3],
+    color: [u8; 4],
+}
+
+fn main() {}
@@ -0,0 +1,175 @@
+//! WGSL-like names of naga types and shader stages, shared by the reflection reports of
+//! shader-builder and the pipeline checks of viewport so both describe a shader the same way.
+
+use naga::{
+    ArraySize, ConstantInner, Handle, ImageClass, ImageDimension, Module, ScalarKind, ScalarValue,
+    ShaderStage, Type, TypeInner, VectorSize,
+};
+
+/// Length of an array whose size is the given constant, if it is a valid `u32`.
+pub fn array_length(module: &Module, constant: Handle<naga::Constant>) -> Option<u32> {
+    match module.constants[constant].inner {
+        ConstantInner::Scalar {
+            value: ScalarValue::Uint(length),
+            ..
+        } => u32::try_from(length).ok(),
+        ConstantInner::Scalar {
+            value: ScalarValue::Sint(length),
+            ..
+        } => u32::try_from(length).ok(),
+        _ => None,
+    }
+}
+
+/// Lowercase name of a shader stage, e.g. `vertex`.
+pub fn stage_name(stage: ShaderStage) -> &'static str {
+    match stage {
+        ShaderStage::Vertex => "vertex",
+        ShaderStage::Fragment => "fragment",
+        ShaderStage::Compute => "compute",
+    }
+}
+
+/// Number of components of a vector size.
+pub fn vector_size(size: VectorSize) -> u8 {
+    match size {
+        VectorSize::Bi => 2,
+        VectorSize::Tri => 3,
+        VectorSize::Quad => 4,
+    }
+}
+
+/// Name of a scalar type in WGSL syntax, e.g. `f32`.
+pub fn scalar_name(kind: ScalarKind, width: u8) -> String {
+    match kind {
+        ScalarKind::Sint => format!("i{}", width * 8),
+        ScalarKind::Uint => format!("u{}", width * 8),
+        ScalarKind::Float => format!("f{}", width * 8),
+        ScalarKind::Bool => "bool".to_string(),
+    }
+}
+
+/// Name of a type in WGSL-like syntax, e.g. `vec3<f32>` or `array<u32>`.
+pub fn type_name(module: &Module, ty: Handle<Type>) -> String {
+    let ty = &module.types[ty];
+    match &ty.inner {
+        TypeInner::Scalar { kind, width } => scalar_name(*kind, *width),
+        TypeInner::Vector { size, kind, width } => {
+            format!("vec{}<{}>", vector_size(*size), scalar_name(*kind, *width))
+        }
+        TypeInner::Matrix {
+            columns,
+            rows,
+            width,
+        } => format!(
+            "mat{}x{}<{}>",
+            vector_size(*columns),
+            vector_size(*rows),
+            scalar_name(ScalarKind::Float, *width)
+        ),
+        TypeInner::Atomic { kind, width } => format!("atomic<{}>", scalar_name(*kind, *width)),
+        TypeInner::Pointer { base, .. } => format!("ptr<{}>", type_name(module, *base)),
+        TypeInner::ValuePointer {
+            size, kind, width, ..
+        } => match size {
+            Some(size) => format!(
+                "ptr<vec{}<{}>>",
+                vector_size(*size),
+                scalar_name(*kind, *width)
+            ),
+            None => format!("ptr<{}>", scalar_name(*kind, *width)),
+        },
+        TypeInner::Array { base, size, .. } | TypeInner::BindingArray { base, size } => {
+            let array = match ty.inner {
+                TypeInner::BindingArray { .. } => "binding_array",
+                _ => "array",
+            };
+            match size {
+                ArraySize::Dynamic => format!("{array}<{}>", type_name(module, *base)),
+                ArraySize::Constant(constant) => match array_length(module, *constant) {
+                    Some(length) => format!("{array}<{}, {length}>", type_name(module, *base)),
+                    None => format!("{array}<{}, ?>", type_name(module, *base)),
+                },
+            }
+        }
+        TypeInner::Struct { members, .. } => match &ty.name {
+            Some(name) => name.clone(),
+            // rust-gpu wraps buffers in anonymous structs, so spell out what's inside
+            None => {
+                let members = members
+                    .iter()
+                    .map(|member| type_name(module, member.ty))
+                    .collect::<Vec<_>>();
+                format!("struct {{ {} }}", members.join(", "))
+            }
+        },
+        TypeInner::Image {
+            dim,
+            arrayed,
+            class,
+        } => {
+            let dimension = match (dim, arrayed) {
+                (ImageDimension::D1, _) => "1d",
+                (ImageDimension::D2, false) => "2d",
+                (ImageDimension::D2, true) => "2d_array",
+                (ImageDimension::D3, _) => "3d",
+                (ImageDimension::Cube, false) => "cube",
+                (ImageDimension::Cube, true) => "cube_array",
+            };
+            match class {
+                ImageClass::Sampled { kind, multi: false } => {
+                    format!("texture_{dimension}<{}>", scalar_name(*kind, 4))
+                }
+                ImageClass::Sampled { kind, multi: true } => {
+                    format!(
+                        "texture_multisampled_{dimension}<{}>",
+                        scalar_name(*kind, 4)
+                    )
+                }
+                ImageClass::Depth { .. } => format!("texture_depth_{dimension}"),
+                ImageClass::Storage { format, .. } => {
+                    format!("texture_storage_{dimension}<{format:?}>")
+                }
+            }
+        }
+        TypeInner::Sampler { comparison: false } => "sampler".to_string(),
+        TypeInner::Sampler { comparison: true } => "sampler_comparison".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_types_like_wgsl() {
+        let module = naga::front::wgsl::parse_str(
+            "
+            struct Light { color: vec3<f32>, intensity: f32 }
+            @group(0) @binding(0) var<storage> lights: array<Light>;
+            @group(0) @binding(1) var<storage> counts: array<atomic<u32>, 4>;
+            @group(0) @binding(2) var textures: binding_array<texture_2d_array<i32>, 2>;
+            @group(0) @binding(3) var shadow: texture_depth_cube;
+            @group(0) @binding(4) var<uniform> transform: mat4x3<f32>;
+            ",
+        )
+        .unwrap();
+
+        let names = module
+            .global_variables
+            .iter()
+            .map(|(_, global)| type_name(&module, global.ty))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            [
+                "array<Light>",
+                "array<atomic<u32>, 4>",
+                "binding_array<texture_2d_array<i32>, 2>",
+                "texture_depth_cube",
+                "mat4x3<f32>",
+            ]
+        );
+        assert_eq!(stage_name(ShaderStage::Compute), "compute");
+    }
+}
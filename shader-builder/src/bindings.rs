@@ -0,0 +1,321 @@
+use std::fmt::Write;
+
+use crate::reflect::{BindingCount, BindingKind, Reflection};
+
+/// Generate a Rust module with the host-side counterparts of a shader's interface, written next
+/// to it as `<shader>.rs`:
+///
+/// - `<ENTRY_POINT>`: the name of every entry point.
+/// - `BIND_GROUP_<n>`: a `wgpu::BindGroupLayoutDescriptor` for every bind group, with its entries
+///   in `BIND_GROUP_<n>_ENTRIES`.
+/// - `<ENTRY_POINT>_VERTEX_ATTRIBUTES` and `<ENTRY_POINT>_VERTEX_BUFFER_LAYOUT` for vertex shaders,
+///   assuming all inputs come from one vertex buffer, tightly packed in location order.
+///
+/// Sampled float textures and samplers are assumed to be filterable, which the shader can't tell.
+/// Bind groups with runtime sized binding arrays and vertex shaders with inputs no vertex format
+/// matches are left out, with a comment saying why, as their layouts would be wrong.
+pub fn generate(shader: &str, reflection: &Reflection) -> String {
+    let mut out = String::new();
+    writeln!(
+        out,
+        "// Generated by shader-builder from {shader}.spv, do not edit."
+    )
+    .unwrap();
+    writeln!(out).unwrap();
+
+    for entry_point in &reflection.entry_points {
+        writeln!(
+            out,
+            "pub const {}: &str = {:?};",
+            constant_name(&entry_point.name),
+            entry_point.name
+        )
+        .unwrap();
+    }
+
+    let mut groups = reflection
+        .bindings
+        .iter()
+        .map(|binding| binding.group)
+        .collect::<Vec<_>>();
+    groups.dedup();
+    for group in groups {
+        writeln!(out).unwrap();
+        let runtime_sized = reflection.bindings.iter().find(|binding| {
+            binding.group == group && binding.count == Some(BindingCount::RuntimeSized)
+        });
+        if let Some(binding) = runtime_sized {
+            writeln!(
+                out,
+                "// No BIND_GROUP_{group}: binding {} is a runtime sized binding array, \
+                whose count only the host knows.",
+                binding.binding
+            )
+            .unwrap();
+            continue;
+        }
+        writeln!(
+            out,
+            "pub const BIND_GROUP_{group}_ENTRIES: &[wgpu::BindGroupLayoutEntry] = &["
+        )
+        .unwrap();
+        for binding in reflection.bindings.iter().filter(|b| b.group == group) {
+            if let Some(name) = &binding.name {
+                writeln!(out, "    // {name}").unwrap();
+            }
+            writeln!(out, "    wgpu::BindGroupLayoutEntry {{").unwrap();
+            writeln!(out, "        binding: {},", binding.binding).unwrap();
+            writeln!(out, "        visibility: {},", visibility(&binding.stages)).unwrap();
+            writeln!(out, "        ty: {},", binding_type(&binding.kind)).unwrap();
+            let count = match binding.count {
+                Some(BindingCount::Fixed(count)) => format!("::std::num::NonZeroU32::new({count})"),
+                _ => "None".to_string(),
+            };
+            writeln!(out, "        count: {count},").unwrap();
+            writeln!(out, "    }},").unwrap();
+        }
+        writeln!(out, "];").unwrap();
+        writeln!(
+            out,
+            "pub const BIND_GROUP_{group}: wgpu::BindGroupLayoutDescriptor<'static> = \
+            wgpu::BindGroupLayoutDescriptor {{"
+        )
+        .unwrap();
+        writeln!(
+            out,
+            "    label: Some({:?}),",
+            format!("{shader} bind group {group}")
+        )
+        .unwrap();
+        writeln!(out, "    entries: BIND_GROUP_{group}_ENTRIES,").unwrap();
+        writeln!(out, "}};").unwrap();
+    }
+
+    for entry_point in reflection
+        .entry_points
+        .iter()
+        .filter(|entry_point| entry_point.stage == "vertex")
+    {
+        let name = constant_name(&entry_point.name);
+        let mut inputs = vec![];
+        let mut unmapped = vec![];
+        for input in &entry_point.inputs {
+            // Built-ins don't come from vertex buffers
+            let Some(location) = input.location else {
+                continue;
+            };
+            match input.vertex_format {
+                Some(format) => inputs.push((location, format)),
+                None => unmapped.push(format!("location {location} ({})", input.ty)),
+            }
+        }
+        inputs.sort();
+
+        writeln!(out).unwrap();
+        if !unmapped.is_empty() {
+            writeln!(
+                out,
+                "// No {name}_VERTEX_BUFFER_LAYOUT: no vertex format matches the input(s) at {}.",
+                unmapped.join(", ")
+            )
+            .unwrap();
+            continue;
+        }
+        let mut offset = 0;
+        writeln!(
+            out,
+            "pub const {name}_VERTEX_ATTRIBUTES: &[wgpu::VertexAttribute] = &["
+        )
+        .unwrap();
+        for (location, format) in inputs {
+            writeln!(out, "    wgpu::VertexAttribute {{").unwrap();
+            writeln!(out, "        format: wgpu::VertexFormat::{format},").unwrap();
+            writeln!(out, "        offset: {offset},").unwrap();
+            writeln!(out, "        shader_location: {location},").unwrap();
+            writeln!(out, "    }},").unwrap();
+            offset += vertex_format_size(format);
+        }
+        writeln!(out, "];").unwrap();
+        writeln!(
+            out,
+            "pub const {name}_VERTEX_BUFFER_LAYOUT: wgpu::VertexBufferLayout<'static> = \
+            wgpu::VertexBufferLayout {{"
+        )
+        .unwrap();
+        writeln!(out, "    array_stride: {offset},").unwrap();
+        writeln!(out, "    step_mode: wgpu::VertexStepMode::Vertex,").unwrap();
+        writeln!(out, "    attributes: {name}_VERTEX_ATTRIBUTES,").unwrap();
+        writeln!(out, "}};").unwrap();
+    }
+
+    out
+}
+
+/// `main_vs` -> `MAIN_VS`
+fn constant_name(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_uppercase()
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+fn visibility(stages: &[&str]) -> String {
+    let stages = stages
+        .iter()
+        .map(|stage| format!("wgpu::ShaderStages::{}", stage.to_uppercase()))
+        .collect::<Vec<_>>();
+    match stages.split_first() {
+        None => "wgpu::ShaderStages::NONE".to_string(),
+        Some((first, rest)) => rest.iter().fold(first.clone(), |visibility, stage| {
+            format!("{visibility}.union({stage})")
+        }),
+    }
+}
+
+fn binding_type(kind: &BindingKind) -> String {
+    match kind {
+        BindingKind::UniformBuffer => "wgpu::BindingType::Buffer { \
+            ty: wgpu::BufferBindingType::Uniform, \
+            has_dynamic_offset: false, \
+            min_binding_size: None }"
+            .to_string(),
+        BindingKind::StorageBuffer { read_only } => format!(
+            "wgpu::BindingType::Buffer {{ \
+            ty: wgpu::BufferBindingType::Storage {{ read_only: {read_only} }}, \
+            has_dynamic_offset: false, \
+            min_binding_size: None }}"
+        ),
+        BindingKind::Texture {
+            dimension,
+            sample_type,
+            multisampled,
+        } => {
+            let sample_type = match *sample_type {
+                "Float" => "Float { filterable: true }",
+                sample_type => sample_type,
+            };
+            format!(
+                "wgpu::BindingType::Texture {{ \
+                sample_type: wgpu::TextureSampleType::{sample_type}, \
+                view_dimension: wgpu::TextureViewDimension::{dimension}, \
+                multisampled: {multisampled} }}"
+            )
+        }
+        BindingKind::StorageTexture {
+            dimension,
+            format,
+            access,
+        } => format!(
+            "wgpu::BindingType::StorageTexture {{ \
+            access: wgpu::StorageTextureAccess::{access}, \
+            format: wgpu::TextureFormat::{format}, \
+            view_dimension: wgpu::TextureViewDimension::{dimension} }}"
+        ),
+        BindingKind::Sampler { comparison: false } => {
+            "wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering)".to_string()
+        }
+        BindingKind::Sampler { comparison: true } => {
+            "wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Comparison)".to_string()
+        }
+    }
+}
+
+/// Size in bytes of the vertex formats reflection produces.
+fn vertex_format_size(format: &str) -> u64 {
+    let (scalar_size, components) = match format.split_once('x') {
+        Some((scalar, components)) => (scalar, components.parse().unwrap()),
+        None => (format, 1),
+    };
+    let scalar_size = if scalar_size.ends_with("64") { 8 } else { 4 };
+    scalar_size * components
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reflect::reflect_module;
+
+    fn generate_wgsl(source: &str) -> String {
+        let module = naga::front::wgsl::parse_str(source).unwrap();
+        generate("test", &reflect_module(&module))
+    }
+
+    #[test]
+    fn generates_packed_vertex_layout_and_binding_arrays() {
+        let bindings = generate_wgsl(
+            "
+            @group(0) @binding(0) var textures: binding_array<texture_2d<f32>, 4>;
+            @group(0) @binding(1) var linear: sampler;
+
+            @vertex
+            fn main_vs(
+                @location(1) uv: vec2<f32>,
+                @location(0) position: vec3<f32>,
+                @builtin(vertex_index) index: u32,
+            ) -> @builtin(position) vec4<f32> {
+                return textureSampleLevel(textures[0], linear, uv, 0.0) + vec4<f32>(position, 1.0);
+            }
+            ",
+        );
+
+        assert!(bindings.contains("pub const MAIN_VS: &str = \"main_vs\";"));
+        assert!(bindings.contains(
+            "        binding: 0,\n        \
+            visibility: wgpu::ShaderStages::VERTEX,\n        \
+            ty: wgpu::BindingType::Texture { sample_type: wgpu::TextureSampleType::Float { \
+            filterable: true }, view_dimension: wgpu::TextureViewDimension::D2, \
+            multisampled: false },\n        \
+            count: ::std::num::NonZeroU32::new(4),\n"
+        ));
+        assert!(bindings.contains(
+            "wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),\n        \
+            count: None,\n"
+        ));
+        assert!(bindings.contains(
+            "    wgpu::VertexAttribute {\n        \
+            format: wgpu::VertexFormat::Float32x3,\n        \
+            offset: 0,\n        \
+            shader_location: 0,\n    },\n    \
+            wgpu::VertexAttribute {\n        \
+            format: wgpu::VertexFormat::Float32x2,\n        \
+            offset: 12,\n        \
+            shader_location: 1,\n    },\n];"
+        ));
+        assert!(bindings.contains("    array_stride: 20,\n"));
+    }
+
+    #[test]
+    fn leaves_out_layouts_it_cant_generate() {
+        let bindings = generate_wgsl(
+            "
+            @group(0) @binding(0) var textures: binding_array<texture_2d<f32>>;
+            @group(0) @binding(1) var linear: sampler;
+
+            @vertex
+            fn main_vs(
+                @location(0) position: vec3<f32>,
+                @location(1) weights: mat2x2<f32>,
+            ) -> @builtin(position) vec4<f32> {
+                let weighted = weights * position.xy;
+                return textureSampleLevel(textures[0], linear, weighted, 0.0);
+            }
+            ",
+        );
+
+        assert!(bindings.contains(
+            "// No BIND_GROUP_0: binding 0 is a runtime sized binding array, whose count only \
+            the host knows."
+        ));
+        assert!(!bindings.contains("BIND_GROUP_0_ENTRIES"));
+        assert!(bindings.contains(
+            "// No MAIN_VS_VERTEX_BUFFER_LAYOUT: no vertex format matches the input(s) at \
+            location 1 (mat2x2<f32>)."
+        ));
+        assert!(!bindings.contains("MAIN_VS_VERTEX_ATTRIBUTES"));
+    }
+}
@@ -0,0 +1,82 @@
+use std::path::PathBuf;
+
+use clap::{ArgAction, Parser, ValueEnum};
+use serde::Deserialize;
+use spirv_builder::Capability;
+
+/// Compile the rust-gpu shader crates in `shaders/` to SPIR-V.
+#[derive(Parser, Debug)]
+#[command(version)]
+pub struct Args {
+    /// Shaders to build, i.e. directory names in `shaders/`. Builds all of them if omitted.
+    pub shaders: Vec<String>,
+
+    /// Build without optimizations, which compiles faster.
+    #[arg(long)]
+    pub debug: bool,
+
+    /// SPIR-V version or Vulkan environment to target, instead of the one configured for the
+    /// shader. Defaults to `spv1.5`.
+    #[arg(long, value_enum)]
+    pub target: Option<TargetEnv>,
+
+    /// SPIR-V capability to enable on top of the ones configured for the shader, e.g. `Int64`.
+    /// Can be given multiple times.
+    #[arg(long = "capability", value_name = "CAPABILITY", value_parser = parse_capability)]
+    pub capabilities: Vec<Capability>,
+
+    /// SPIR-V extension to enable on top of the ones configured for the shader, e.g.
+    /// `SPV_KHR_ray_query`. Can be given multiple times.
+    #[arg(long = "extension", value_name = "EXTENSION")]
+    pub extensions: Vec<String>,
+
+    /// Directory the `<shader>.spv` files are written to.
+    #[arg(long, default_value = "target")]
+    pub output_dir: PathBuf,
+
+    /// Keep running and rebuild a shader whenever its `src/` or `Cargo.toml` changes.
+    #[arg(long)]
+    pub watch: bool,
+
+    /// Print more about every build, e.g. the settings and entry points.
+    #[arg(short, long, action = ArgAction::Count, conflicts_with = "quiet")]
+    pub verbose: u8,
+
+    /// Only print errors.
+    #[arg(short, long)]
+    pub quiet: bool,
+}
+
+#[derive(Clone, Copy, Debug, Default, ValueEnum, Deserialize)]
+pub enum TargetEnv {
+    #[value(name = "spv1.3")]
+    #[serde(rename = "spv1.3")]
+    Spv1_3,
+    #[default]
+    #[value(name = "spv1.5")]
+    #[serde(rename = "spv1.5")]
+    Spv1_5,
+    #[value(name = "vulkan1.1")]
+    #[serde(rename = "vulkan1.1")]
+    Vulkan1_1,
+    #[value(name = "vulkan1.2")]
+    #[serde(rename = "vulkan1.2")]
+    Vulkan1_2,
+}
+
+impl TargetEnv {
+    pub fn target(self) -> &'static str {
+        match self {
+            TargetEnv::Spv1_3 => "spirv-unknown-spv1.3",
+            TargetEnv::Spv1_5 => "spirv-unknown-spv1.5",
+            TargetEnv::Vulkan1_1 => "spirv-unknown-vulkan1.1",
+            TargetEnv::Vulkan1_2 => "spirv-unknown-vulkan1.2",
+        }
+    }
+}
+
+pub fn parse_capability(capability: &str) -> Result<Capability, String> {
+    capability
+        .parse()
+        .map_err(|_| format!("unknown SPIR-V capability '{capability}'"))
+}
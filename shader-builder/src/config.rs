@@ -0,0 +1,71 @@
+use std::{fs, path::Path};
+
+use serde::{de, Deserialize, Deserializer};
+use spirv_builder::Capability;
+
+use crate::cli::{parse_capability, TargetEnv};
+
+/// Build settings of a shader crate, read from the `[package.metadata.rust-gpu]` section of its
+/// `Cargo.toml`:
+///
+/// ```toml
+/// [package.metadata.rust-gpu]
+/// capabilities = ["Int8", "Int64"]
+/// extensions = ["SPV_KHR_non_semantic_info"]
+/// target = "vulkan1.1"
+/// multimodule = false
+/// relax-logical-pointer = false
+/// require-bindings = false
+/// ```
+///
+/// Every setting is optional.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub struct ShaderConfig {
+    #[serde(deserialize_with = "deserialize_capabilities")]
+    pub capabilities: Vec<Capability>,
+    pub extensions: Vec<String>,
+    pub target: Option<TargetEnv>,
+    /// Write one module per entry point, to `<output dir>/<shader>/<entry point>.spv`.
+    pub multimodule: bool,
+    pub relax_logical_pointer: bool,
+    /// Fail the build if the reflection report and bindings module can't be generated, for
+    /// shaders whose bindings a host crate includes.
+    pub require_bindings: bool,
+}
+
+#[derive(Deserialize)]
+struct Manifest {
+    package: Package,
+}
+
+#[derive(Deserialize)]
+struct Package {
+    #[serde(default)]
+    metadata: Metadata,
+}
+
+#[derive(Default, Deserialize)]
+struct Metadata {
+    #[serde(default, rename = "rust-gpu")]
+    rust_gpu: ShaderConfig,
+}
+
+fn deserialize_capabilities<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Vec<Capability>, D::Error> {
+    Vec::<String>::deserialize(deserializer)?
+        .iter()
+        .map(|capability| parse_capability(capability).map_err(de::Error::custom))
+        .collect()
+}
+
+impl ShaderConfig {
+    /// Read the configuration from a shader crate's manifest, the default configuration if it
+    /// has no `[package.metadata.rust-gpu]` section.
+    pub fn load(manifest_path: &Path) -> Result<Self, String> {
+        let manifest = fs::read_to_string(manifest_path).map_err(|e| e.to_string())?;
+        let manifest: Manifest = toml::from_str(&manifest).map_err(|e| e.to_string())?;
+        Ok(manifest.package.metadata.rust_gpu)
+    }
+}
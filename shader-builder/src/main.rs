@@ -1,13 +1,57 @@
-use std::{env, fs};
+mod bindings;
+mod cli;
+mod config;
+mod reflect;
+mod watch;
 
-use spirv_builder::{Capability, MetadataPrintout, SpirvBuilder};
+use std::{
+    env, fs, io,
+    path::{Path, PathBuf},
+};
 
-const SHADERS: &[&str] = &["triangle", "mandelbrot", "textures"];
+use clap::Parser;
+use cli::Args;
+use config::ShaderConfig;
+use spirv_builder::{MetadataPrintout, ModuleResult, SpirvBuilder};
 
-// This file is adapted from Strolle's shader builder. 
+/// Directory containing one crate per shader, each compiled to `<output dir>/<directory name>.spv`.
+const SHADERS_DIR: &str = "shaders";
+
+// This file is adapted from Strolle's shader builder.
 // See: https://github.com/yuyttenhove/strolle/tree/main/strolle-shader-builder
 
+/// Names of all shader crates, i.e. the directories in `shaders/` containing a `Cargo.toml`.
+fn discover_shaders() -> Vec<String> {
+    let mut shaders = fs::read_dir(SHADERS_DIR)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.join("Cargo.toml").is_file())
+        .map(|path| path.file_name().unwrap().to_string_lossy().into_owned())
+        .collect::<Vec<_>>();
+    shaders.sort();
+    shaders
+}
+
 fn main() {
+    let args = Args::parse();
+
+    // Build the shaders given on the command line, or all of them
+    let shaders = if args.shaders.is_empty() {
+        discover_shaders()
+    } else {
+        args.shaders.clone()
+    };
+    for shader in &shaders {
+        if !Path::new(SHADERS_DIR)
+            .join(shader)
+            .join("Cargo.toml")
+            .is_file()
+        {
+            eprintln!("Unknown shader '{shader}', expected a crate in {SHADERS_DIR}/{shader}");
+            std::process::exit(1);
+        }
+    }
+
     // HACK Normally, when compiling shaders, spirv-builder uses the regular
     //      `target` directory for the results - this poses an inconvenience
     //      when you alternately build shaders and examples (e.g. during
@@ -21,25 +65,144 @@ fn main() {
     //      nested Cargo invocation, which spirv-builder detects and tries to
     //      alleviate on its own, using `--target-dir` - and this fixes the
     //      "artifacts getting randomly invalidated" problem.
-    env::set_var("PROFILE", "release");
-    env::set_var("OUT_DIR", "../../target/spirv/release/build/shader/out");
-
-    for shader in SHADERS {
-        let compile_result = SpirvBuilder::new(
-            format!("shaders/{shader}"),
-            "spirv-unknown-spv1.5",
-        )
+    let profile = if args.debug { "debug" } else { "release" };
+    env::set_var("PROFILE", profile);
+    env::set_var(
+        "OUT_DIR",
+        format!("../../target/spirv/{profile}/build/shader/out"),
+    );
+
+    fs::create_dir_all(&args.output_dir).unwrap();
+
+    let build = |shader: &str| build_shader(&args, shader, profile);
+    let mut failed = false;
+    for shader in &shaders {
+        if let Err(e) = build(shader) {
+            eprintln!("{e}");
+            failed = true;
+        }
+    }
+
+    if args.watch {
+        watch::watch(Path::new(SHADERS_DIR), &shaders, build);
+    } else if failed {
+        std::process::exit(1);
+    }
+}
+
+fn build_shader(args: &Args, shader: &str, profile: &str) -> Result<(), String> {
+    let shader_dir = Path::new(SHADERS_DIR).join(shader);
+    let config = ShaderConfig::load(&shader_dir.join("Cargo.toml"))
+        .map_err(|e| format!("Error reading the build configuration of {shader}: {e}"))?;
+    // The command line takes precedence over the shader's configuration
+    let target = args.target.or(config.target).unwrap_or_default();
+    let capabilities = config
+        .capabilities
+        .iter()
+        .chain(&args.capabilities)
+        .copied()
+        .collect::<Vec<_>>();
+    let extensions = config
+        .extensions
+        .iter()
+        .chain(&args.extensions)
+        .collect::<Vec<_>>();
+
+    if !args.quiet {
+        println!("Building {shader} ({profile}, {})", target.target());
+    }
+    if args.verbose > 0 {
+        println!("  capabilities: {capabilities:?}");
+        println!("  extensions: {extensions:?}");
+        println!("  multimodule: {}", config.multimodule);
+        println!("  relax logical pointer: {}", config.relax_logical_pointer);
+        println!("  require bindings: {}", config.require_bindings);
+    }
+
+    let mut builder = SpirvBuilder::new(shader_dir, target.target())
         .print_metadata(MetadataPrintout::None)
-        .capability(Capability::Int8)
-        .release(true)
+        .release(!args.debug)
+        .multimodule(config.multimodule)
+        .relax_logical_pointer(config.relax_logical_pointer);
+    for capability in capabilities {
+        builder = builder.capability(capability);
+    }
+    for extension in extensions {
+        builder = builder.extension(extension.as_str());
+    }
+    let compile_result = builder
         .build()
-        .unwrap();
+        .map_err(|e| format!("Error building {shader}: {e}"))?;
+
+    if args.verbose > 0 {
+        println!("  entry points: {}", compile_result.entry_points.join(", "));
+    }
+
+    let outputs = match &compile_result.module {
+        ModuleResult::SingleModule(module) => {
+            vec![(module, args.output_dir.join(format!("{shader}.spv")))]
+        }
+        ModuleResult::MultiModule(modules) => {
+            let output_dir = args.output_dir.join(shader);
+            fs::create_dir_all(&output_dir)
+                .map_err(|e| format!("Error creating {}: {e}", output_dir.display()))?;
+            modules
+                .iter()
+                .map(|(entry_point, module)| {
+                    (module, output_dir.join(format!("{entry_point}.spv")))
+                })
+                .collect()
+        }
+    };
+    for (module, output) in outputs {
+        replace_file(module, &output)
+            .map_err(|e| format!("Error writing {}: {e}", output.display()))?;
+        if !args.quiet {
+            println!("Wrote {}", output.display());
+        }
 
-        fs::copy(
-            compile_result.module.unwrap_single(),
-            format!("target/{shader}.spv"),
-        )
-        .unwrap();
+        // naga doesn't understand everything rust-gpu can emit, which shouldn't fail the build,
+        // unless a host crate includes the bindings and would fail to compile without them
+        match write_reflection(&output) {
+            Ok(paths) if args.verbose > 0 => {
+                for path in paths {
+                    println!("Wrote {}", path.display());
+                }
+            }
+            Ok(_) => {}
+            Err(e) if config.require_bindings => {
+                return Err(format!(
+                    "Error generating the bindings of {}: {e}",
+                    output.display()
+                ))
+            }
+            Err(e) => eprintln!("Warning: no reflection for {}: {e}", output.display()),
+        }
     }
+
+    Ok(())
 }
 
+/// Write the reflection report and the generated host bindings next to the module.
+fn write_reflection(module: &Path) -> Result<[PathBuf; 2], String> {
+    let spirv = fs::read(module).map_err(|e| e.to_string())?;
+    let reflection = reflect::reflect(&spirv)?;
+
+    let json_path = module.with_extension("json");
+    let json = serde_json::to_string_pretty(&reflection).map_err(|e| e.to_string())?;
+    fs::write(&json_path, json).map_err(|e| e.to_string())?;
+
+    let bindings_path = module.with_extension("rs");
+    let name = module.file_stem().unwrap().to_string_lossy();
+    fs::write(&bindings_path, bindings::generate(&name, &reflection)).map_err(|e| e.to_string())?;
+
+    Ok([json_path, bindings_path])
+}
+
+/// Copy `from` over `to` through a temporary file, so anything watching `to` (e.g. a running
+/// example with hot reloading) never sees a partially written module.
+fn replace_file(from: &Path, to: &Path) -> io::Result<()> {
+    let tmp = to.with_extension("spv.tmp");
+    fs::copy(from, &tmp)?;
+    fs::rename(&tmp, to)
+}
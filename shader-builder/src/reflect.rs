@@ -0,0 +1,459 @@
+use naga::{
+    valid::{Capabilities, ValidationFlags, Validator},
+    AddressSpace, ArraySize, Binding, BuiltIn, Handle, ImageClass, ImageDimension, Module,
+    ScalarKind, ShaderStage, StorageAccess, Type, TypeInner, VectorSize,
+};
+use naga_names::{array_length, stage_name, type_name};
+use serde::Serialize;
+
+/// What a SPIR-V module exposes to the host, written next to it as `<shader>.json`.
+///
+/// Texture dimensions, sample types and vertex and storage formats are named after the matching
+/// `wgpu` variants, e.g. `D2`, `Float` and `Float32x3`.
+#[derive(Debug, Serialize)]
+pub struct Reflection {
+    pub entry_points: Vec<EntryPoint>,
+    pub bindings: Vec<ResourceBinding>,
+    pub push_constants: Vec<PushConstant>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct EntryPoint {
+    pub name: String,
+    pub stage: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub workgroup_size: Option<[u32; 3]>,
+    pub inputs: Vec<Varying>,
+    pub outputs: Vec<Varying>,
+}
+
+/// An input or output of an entry point, either at a location or a built-in.
+#[derive(Debug, Serialize)]
+pub struct Varying {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub location: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub builtin: Option<String>,
+    #[serde(rename = "type")]
+    pub ty: String,
+    /// The vertex format matching the type, for vertex shader inputs at a location.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vertex_format: Option<&'static str>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ResourceBinding {
+    pub group: u32,
+    pub binding: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(flatten)]
+    pub kind: BindingKind,
+    /// Number of resources in a binding array, `None` for single resources.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub count: Option<BindingCount>,
+    #[serde(rename = "type")]
+    pub ty: String,
+    /// Stages of the entry points using the binding.
+    pub stages: Vec<&'static str>,
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BindingCount {
+    Fixed(u32),
+    RuntimeSized,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum BindingKind {
+    UniformBuffer,
+    StorageBuffer {
+        read_only: bool,
+    },
+    Texture {
+        dimension: &'static str,
+        sample_type: &'static str,
+        multisampled: bool,
+    },
+    StorageTexture {
+        dimension: &'static str,
+        format: String,
+        access: &'static str,
+    },
+    Sampler {
+        comparison: bool,
+    },
+}
+
+#[derive(Debug, Serialize)]
+pub struct PushConstant {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(rename = "type")]
+    pub ty: String,
+    pub size: u32,
+}
+
+/// Reflect a SPIR-V module, as written by the shader builder.
+pub fn reflect(spirv: &[u8]) -> Result<Reflection, String> {
+    let module = naga::front::spv::parse_u8_slice(spirv, &Default::default())
+        .map_err(|e| format!("Error parsing SPIR-V: {e}"))?;
+    Ok(reflect_module(&module))
+}
+
+pub fn reflect_module(module: &Module) -> Reflection {
+    let entry_points = module
+        .entry_points
+        .iter()
+        .map(|entry_point| {
+            let function = &entry_point.function;
+            let mut inputs = vec![];
+            for argument in function.arguments.iter() {
+                varyings(
+                    module,
+                    entry_point.stage == ShaderStage::Vertex,
+                    argument.name.as_ref(),
+                    argument.ty,
+                    argument.binding.as_ref(),
+                    &mut inputs,
+                );
+            }
+            let mut outputs = vec![];
+            if let Some(result) = &function.result {
+                varyings(
+                    module,
+                    false,
+                    None,
+                    result.ty,
+                    result.binding.as_ref(),
+                    &mut outputs,
+                );
+            }
+            EntryPoint {
+                name: entry_point.name.clone(),
+                stage: stage_name(entry_point.stage),
+                workgroup_size: (entry_point.stage == ShaderStage::Compute)
+                    .then_some(entry_point.workgroup_size),
+                inputs,
+                outputs,
+            }
+        })
+        .collect::<Vec<_>>();
+
+    // Only the analysis is needed to find which entry points use which globals, the module is
+    // assumed to be valid. Without it, bindings are reported as used by every stage.
+    let info = Validator::new(ValidationFlags::empty(), Capabilities::all())
+        .validate(module)
+        .ok();
+
+    let mut bindings = vec![];
+    let mut push_constants = vec![];
+    for (handle, global) in module.global_variables.iter() {
+        if global.space == AddressSpace::PushConstant {
+            push_constants.push(PushConstant {
+                name: global.name.clone(),
+                ty: type_name(module, global.ty),
+                size: module.types[global.ty].inner.size(&module.constants),
+            });
+            continue;
+        }
+        let (Some(binding), Some(kind)) = (
+            &global.binding,
+            binding_kind(module, global.space, global.ty),
+        ) else {
+            continue;
+        };
+
+        let mut stages = vec![];
+        for (index, entry_point) in module.entry_points.iter().enumerate() {
+            let used = match &info {
+                Some(info) => !info.get_entry_point(index)[handle].is_empty(),
+                None => true,
+            };
+            let stage = stage_name(entry_point.stage);
+            if used && !stages.contains(&stage) {
+                stages.push(stage);
+            }
+        }
+
+        bindings.push(ResourceBinding {
+            group: binding.group,
+            binding: binding.binding,
+            name: global.name.clone(),
+            kind,
+            count: binding_count(module, global.ty),
+            ty: type_name(module, global.ty),
+            stages,
+        });
+    }
+    bindings.sort_by_key(|binding| (binding.group, binding.binding));
+
+    Reflection {
+        entry_points,
+        bindings,
+        push_constants,
+    }
+}
+
+fn varyings(
+    module: &Module,
+    vertex_input: bool,
+    name: Option<&String>,
+    ty: Handle<Type>,
+    binding: Option<&Binding>,
+    varyings: &mut Vec<Varying>,
+) {
+    match (binding, &module.types[ty].inner) {
+        (Some(binding), inner) => {
+            let (location, builtin, vertex_format) = match binding {
+                Binding::Location { location, .. } => (
+                    Some(*location),
+                    None,
+                    vertex_input.then(|| vertex_format(inner)).flatten(),
+                ),
+                Binding::BuiltIn(BuiltIn::Position { .. }) => {
+                    (None, Some("Position".to_string()), None)
+                }
+                Binding::BuiltIn(builtin) => (None, Some(format!("{builtin:?}")), None),
+            };
+            varyings.push(Varying {
+                name: name.cloned(),
+                location,
+                builtin,
+                ty: type_name(module, ty),
+                vertex_format,
+            });
+        }
+        // Inputs and outputs can be grouped in a struct, whose members have the bindings
+        (None, TypeInner::Struct { members, .. }) => {
+            for member in members {
+                self::varyings(
+                    module,
+                    vertex_input,
+                    member.name.as_ref(),
+                    member.ty,
+                    member.binding.as_ref(),
+                    varyings,
+                );
+            }
+        }
+        (None, _) => {}
+    }
+}
+
+fn binding_kind(module: &Module, space: AddressSpace, ty: Handle<Type>) -> Option<BindingKind> {
+    match space {
+        AddressSpace::Uniform => Some(BindingKind::UniformBuffer),
+        AddressSpace::Storage { access } => Some(BindingKind::StorageBuffer {
+            read_only: !access.contains(StorageAccess::STORE),
+        }),
+        AddressSpace::Handle => match &module.types[ty].inner {
+            TypeInner::Image {
+                dim,
+                arrayed,
+                class,
+            } => {
+                let dimension = dimension_name(*dim, *arrayed);
+                Some(match class {
+                    ImageClass::Sampled { kind, multi } => BindingKind::Texture {
+                        dimension,
+                        sample_type: match kind {
+                            ScalarKind::Sint => "Sint",
+                            ScalarKind::Uint => "Uint",
+                            ScalarKind::Float | ScalarKind::Bool => "Float",
+                        },
+                        multisampled: *multi,
+                    },
+                    ImageClass::Depth { multi } => BindingKind::Texture {
+                        dimension,
+                        sample_type: "Depth",
+                        multisampled: *multi,
+                    },
+                    ImageClass::Storage { format, access } => BindingKind::StorageTexture {
+                        dimension,
+                        format: format!("{format:?}"),
+                        access: match (
+                            access.contains(StorageAccess::LOAD),
+                            access.contains(StorageAccess::STORE),
+                        ) {
+                            (true, false) => "ReadOnly",
+                            (false, true) => "WriteOnly",
+                            _ => "ReadWrite",
+                        },
+                    },
+                })
+            }
+            TypeInner::Sampler { comparison } => Some(BindingKind::Sampler {
+                comparison: *comparison,
+            }),
+            TypeInner::BindingArray { base, .. } => binding_kind(module, space, *base),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn binding_count(module: &Module, ty: Handle<Type>) -> Option<BindingCount> {
+    match module.types[ty].inner {
+        TypeInner::BindingArray {
+            size: ArraySize::Constant(constant),
+            ..
+        } => array_length(module, constant).map(BindingCount::Fixed),
+        TypeInner::BindingArray {
+            size: ArraySize::Dynamic,
+            ..
+        } => Some(BindingCount::RuntimeSized),
+        _ => None,
+    }
+}
+
+fn dimension_name(dim: ImageDimension, arrayed: bool) -> &'static str {
+    match (dim, arrayed) {
+        (ImageDimension::D1, _) => "D1",
+        (ImageDimension::D2, false) => "D2",
+        (ImageDimension::D2, true) => "D2Array",
+        (ImageDimension::D3, _) => "D3",
+        (ImageDimension::Cube, false) => "Cube",
+        (ImageDimension::Cube, true) => "CubeArray",
+    }
+}
+
+fn vertex_format(inner: &TypeInner) -> Option<&'static str> {
+    let (size, kind, width) = match *inner {
+        TypeInner::Scalar { kind, width } => (None, kind, width),
+        TypeInner::Vector { size, kind, width } => (Some(size), kind, width),
+        _ => return None,
+    };
+    Some(match (kind, width, size) {
+        (ScalarKind::Float, 4, None) => "Float32",
+        (ScalarKind::Float, 4, Some(VectorSize::Bi)) => "Float32x2",
+        (ScalarKind::Float, 4, Some(VectorSize::Tri)) => "Float32x3",
+        (ScalarKind::Float, 4, Some(VectorSize::Quad)) => "Float32x4",
+        (ScalarKind::Float, 8, None) => "Float64",
+        (ScalarKind::Float, 8, Some(VectorSize::Bi)) => "Float64x2",
+        (ScalarKind::Float, 8, Some(VectorSize::Tri)) => "Float64x3",
+        (ScalarKind::Float, 8, Some(VectorSize::Quad)) => "Float64x4",
+        (ScalarKind::Sint, 4, None) => "Sint32",
+        (ScalarKind::Sint, 4, Some(VectorSize::Bi)) => "Sint32x2",
+        (ScalarKind::Sint, 4, Some(VectorSize::Tri)) => "Sint32x3",
+        (ScalarKind::Sint, 4, Some(VectorSize::Quad)) => "Sint32x4",
+        (ScalarKind::Uint, 4, None) => "Uint32",
+        (ScalarKind::Uint, 4, Some(VectorSize::Bi)) => "Uint32x2",
+        (ScalarKind::Uint, 4, Some(VectorSize::Tri)) => "Uint32x3",
+        (ScalarKind::Uint, 4, Some(VectorSize::Quad)) => "Uint32x4",
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SHADER: &str = "
+        struct VertexOutput {
+            @builtin(position) position: vec4<f32>,
+            @location(0) uv: vec2<f32>,
+        }
+
+        @group(0) @binding(0) var<uniform> scale: f32;
+        @group(0) @binding(1) var<storage, read_write> counts: array<u32>;
+        @group(1) @binding(0) var textures: binding_array<texture_2d<f32>, 4>;
+        @group(1) @binding(1) var linear: sampler;
+
+        @vertex
+        fn main_vs(
+            @location(0) position: vec3<f32>,
+            @location(1) uv: vec2<f32>,
+            @builtin(vertex_index) index: u32,
+        ) -> VertexOutput {
+            return VertexOutput(vec4<f32>(position * scale, 1.0), uv);
+        }
+
+        @fragment
+        fn main_fs(in: VertexOutput) -> @location(0) vec4<f32> {
+            counts[0] = 1u;
+            return textureSample(textures[0], linear, in.uv);
+        }
+    ";
+
+    #[test]
+    fn reflects_entry_points_and_bindings() {
+        let module = naga::front::wgsl::parse_str(SHADER).unwrap();
+        let reflection = reflect_module(&module);
+
+        let [vertex, fragment] = &reflection.entry_points[..] else {
+            panic!("Expected two entry points: {:?}", reflection.entry_points);
+        };
+        assert_eq!((vertex.name.as_str(), vertex.stage), ("main_vs", "vertex"));
+        let inputs = vertex
+            .inputs
+            .iter()
+            .map(|input| {
+                (
+                    input.location,
+                    input.builtin.as_deref(),
+                    input.vertex_format,
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            inputs,
+            [
+                (Some(0), None, Some("Float32x3")),
+                (Some(1), None, Some("Float32x2")),
+                (None, Some("VertexIndex"), None),
+            ]
+        );
+        assert_eq!(
+            (fragment.name.as_str(), fragment.stage),
+            ("main_fs", "fragment")
+        );
+        assert_eq!(fragment.outputs[0].location, Some(0));
+        assert_eq!(fragment.outputs[0].ty, "vec4<f32>");
+
+        let bindings = reflection
+            .bindings
+            .iter()
+            .map(|binding| (binding.group, binding.binding, &binding.stages[..]))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            bindings,
+            [
+                (0, 0, &["vertex"][..]),
+                (0, 1, &["fragment"][..]),
+                (1, 0, &["fragment"][..]),
+                (1, 1, &["fragment"][..]),
+            ]
+        );
+        assert!(matches!(
+            reflection.bindings[0].kind,
+            BindingKind::UniformBuffer
+        ));
+        assert!(matches!(
+            reflection.bindings[1].kind,
+            BindingKind::StorageBuffer { read_only: false }
+        ));
+        assert!(matches!(
+            reflection.bindings[2].kind,
+            BindingKind::Texture {
+                dimension: "D2",
+                sample_type: "Float",
+                multisampled: false,
+            }
+        ));
+        assert_eq!(reflection.bindings[2].count, Some(BindingCount::Fixed(4)));
+        assert_eq!(
+            reflection.bindings[2].ty,
+            "binding_array<texture_2d<f32>, 4>"
+        );
+        assert!(matches!(
+            reflection.bindings[3].kind,
+            BindingKind::Sampler { comparison: false }
+        ));
+        assert_eq!(reflection.bindings[3].count, None);
+    }
+}
@@ -0,0 +1,54 @@
+use std::{
+    fs,
+    path::Path,
+    thread,
+    time::{Duration, SystemTime},
+};
+
+/// How often the shader sources are checked for changes.
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Latest modification time of a shader crate's `Cargo.toml` and the files in its `src/`.
+fn last_modified(shader_dir: &Path) -> Option<SystemTime> {
+    fn visit(path: &Path, latest: &mut Option<SystemTime>) {
+        let Ok(metadata) = fs::metadata(path) else {
+            return;
+        };
+        if let Ok(modified) = metadata.modified() {
+            *latest = (*latest).max(Some(modified));
+        }
+        if metadata.is_dir() {
+            for entry in fs::read_dir(path).into_iter().flatten().flatten() {
+                visit(&entry.path(), latest);
+            }
+        }
+    }
+
+    let mut latest = None;
+    visit(&shader_dir.join("Cargo.toml"), &mut latest);
+    visit(&shader_dir.join("src"), &mut latest);
+    latest
+}
+
+/// Rebuild every shader whose sources change, forever. Errors are printed and the previous
+/// build of the shader is left in place.
+pub fn watch(shaders_dir: &Path, shaders: &[String], build: impl Fn(&str) -> Result<(), String>) {
+    let mut stamps = shaders
+        .iter()
+        .map(|shader| last_modified(&shaders_dir.join(shader)))
+        .collect::<Vec<_>>();
+    println!("Watching {} for changes", shaders.join(", "));
+
+    loop {
+        thread::sleep(POLL_INTERVAL);
+        for (shader, stamp) in shaders.iter().zip(stamps.iter_mut()) {
+            let modified = last_modified(&shaders_dir.join(shader));
+            if modified != *stamp {
+                *stamp = modified;
+                if let Err(e) = build(shader) {
+                    eprintln!("{e}");
+                }
+            }
+        }
+    }
+}
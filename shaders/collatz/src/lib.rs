@@ -0,0 +1,38 @@
+#![no_std]
+
+use spirv_std::glam::UVec3;
+use spirv_std::spirv;
+
+/// Number of steps it takes for `n` to reach 1, `None` for 0 or if it overflows along the way.
+fn collatz(mut n: u32) -> Option<u32> {
+    if n == 0 {
+        return None;
+    }
+    let mut steps = 0;
+    while n != 1 {
+        if n % 2 == 0 {
+            n /= 2;
+        } else {
+            if n >= 0x5555_5555 {
+                // 3n + 1 would overflow
+                return None;
+            }
+            n = 3 * n + 1;
+        }
+        steps += 1;
+    }
+    Some(steps)
+}
+
+#[spirv(compute(threads(64)))]
+pub fn main_cs(
+    #[spirv(global_invocation_id)] id: UVec3,
+    #[spirv(storage_buffer, descriptor_set = 0, binding = 0)] numbers: &[u32],
+    #[spirv(storage_buffer, descriptor_set = 0, binding = 1)] steps: &mut [u32],
+) {
+    let index = id.x as usize;
+    // The last workgroup can extend past the end of the buffers
+    if index < numbers.len() {
+        steps[index] = collatz(numbers[index]).unwrap_or(u32::MAX);
+    }
+}
@@ -0,0 +1,167 @@
+use models::{primitives, GpuMesh, TexturedVertex, Vertex};
+use viewport::{DresserHooks, RenderPassDresser, ShaderInterface, ShaderInterfaceError, Viewport};
+
+/// Entry points and bind group layouts generated by shader-builder from the textures shader.
+#[allow(dead_code)]
+mod bindings {
+    include!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/../target/textures.rs"
+    ));
+}
+
+pub struct PentagonDresser {
+    render_pipeline_layout: wgpu::PipelineLayout,
+    render_pipeline: wgpu::RenderPipeline,
+    mesh: GpuMesh,
+    diffuse_bind_group: wgpu::BindGroup,
+}
+
+impl PentagonDresser {
+    pub fn new(viewport: &Viewport) -> Self {
+        let device = viewport.device();
+        let queue = viewport.queue();
+
+        // Load the texture
+        let diffuse_bytes = include_bytes!("../happy-tree.png"); // CHANGED!
+        let diffuse_texture =
+            models::Texture::from_bytes(&device, &queue, diffuse_bytes, "happy-tree.png")
+                .expect("Error loading texture!");
+
+        // Create a texture and sampler bind group, laid out as the shader declares them
+        let texture_bind_group_layout = device.create_bind_group_layout(&bindings::BIND_GROUP_0);
+        let diffuse_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            layout: &texture_bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(&diffuse_texture.view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(&diffuse_texture.sampler),
+                },
+            ],
+            label: Some("diffuse_bind_group"),
+        });
+
+        // Load shader, keeping its interface to check the pipeline against. naga doesn't parse
+        // everything rust-gpu emits, in which case the pipeline is created without the check
+        let spirv = wgpu::include_spirv_raw!("../../target/textures.spv");
+        let interface = ShaderInterface::from_spirv(&spirv.source)
+            .map_err(|e| log::warn!("Not checking the pipeline against the shader: {e}"))
+            .ok();
+        let shader = unsafe { device.create_shader_module_spirv(&spirv) };
+
+        // Upload a pentagon, with just the attributes the shader reads
+        let mesh = primitives::polygon(5, 0.5)
+            .map_vertices(|vertex| TexturedVertex {
+                position: vertex.position,
+                texture_coords: vertex.texture_coords,
+            })
+            .upload(device, "Pentagon");
+
+        // Create pipeline
+        let render_pipeline_layout =
+            device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: Some("Render Pipeline Layout"),
+                bind_group_layouts: &[&texture_bind_group_layout],
+                push_constant_ranges: &[],
+            });
+        let render_pipeline = Self::create_render_pipeline(
+            viewport,
+            &render_pipeline_layout,
+            &shader,
+            interface.as_ref(),
+        )
+        .unwrap_or_else(|e| panic!("{e}"));
+
+        Self {
+            render_pipeline_layout,
+            render_pipeline,
+            mesh,
+            diffuse_bind_group,
+        }
+    }
+
+    fn create_render_pipeline(
+        viewport: &Viewport,
+        render_pipeline_layout: &wgpu::PipelineLayout,
+        shader: &wgpu::ShaderModule,
+        interface: Option<&ShaderInterface>,
+    ) -> Result<wgpu::RenderPipeline, ShaderInterfaceError> {
+        let buffers = [TexturedVertex::desc()];
+        let targets = [Some(wgpu::ColorTargetState {
+            format: viewport.surface_format(),
+            blend: Some(wgpu::BlendState::REPLACE),
+            write_mask: wgpu::ColorWrites::ALL,
+        })];
+        let descriptor = wgpu::RenderPipelineDescriptor {
+            label: Some("Render Pipeline"),
+            layout: Some(render_pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: shader,
+                entry_point: bindings::MAIN_VS,
+                buffers: &buffers,
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: shader,
+                entry_point: bindings::MAIN_FS,
+                targets: &targets,
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                strip_index_format: None,
+                front_face: wgpu::FrontFace::Ccw,
+                cull_mode: Some(wgpu::Face::Back),
+                // Setting this to anything other than Fill requires Features::NON_FILL_POLYGON_MODE
+                polygon_mode: wgpu::PolygonMode::Fill,
+                // Requires Features::DEPTH_CLIP_CONTROL
+                unclipped_depth: false,
+                // Requires Features::CONSERVATIVE_RASTERIZATION
+                conservative: false,
+            },
+            depth_stencil: viewport.depth_stencil_state(),
+            multisample: viewport.multisample_state(),
+            multiview: None,
+        };
+        if let Some(interface) = interface {
+            interface.validate_render_pipeline(&descriptor, &[bindings::BIND_GROUP_0_ENTRIES])?;
+        }
+        Ok(viewport.device().create_render_pipeline(&descriptor))
+    }
+}
+
+impl DresserHooks for PentagonDresser {
+    fn reload_pipeline(
+        &self,
+        viewport: &Viewport,
+        shader: &str,
+        module: &wgpu::ShaderModule,
+        interface: &ShaderInterface,
+    ) -> Option<Result<wgpu::RenderPipeline, ShaderInterfaceError>> {
+        (shader == "textures").then(|| {
+            Self::create_render_pipeline(
+                viewport,
+                &self.render_pipeline_layout,
+                module,
+                Some(interface),
+            )
+        })
+    }
+
+    fn replace_pipeline(&mut self, _shader: &str, pipeline: wgpu::RenderPipeline) {
+        self.render_pipeline = pipeline;
+    }
+}
+
+impl RenderPassDresser for PentagonDresser {
+    fn dress<'a, 'b>(&'a self, mut render_pass: wgpu::RenderPass<'b>)
+    where
+        'a: 'b,
+    {
+        render_pass.set_pipeline(&self.render_pipeline);
+        render_pass.set_bind_group(0, &self.diffuse_bind_group, &[]);
+        self.mesh.draw(&mut render_pass);
+    }
+}
@@ -0,0 +1,6 @@
+use textures::PentagonDresser;
+
+#[test]
+fn textures_matches_reference() {
+    golden::assert_dresser_golden!("textures", (256, 256), PentagonDresser::new);
+}
@@ -0,0 +1,6 @@
+use triangle::TriangleDresser;
+
+#[test]
+fn triangle_matches_reference() {
+    golden::assert_dresser_golden!("triangle", (256, 256), TriangleDresser::new);
+}
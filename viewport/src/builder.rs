@@ -0,0 +1,304 @@
+use winit::{dpi::PhysicalSize, event_loop::EventLoop, window::WindowBuilder};
+
+use crate::{
+    viewport::{RenderTarget, HEADLESS_FORMAT},
+    ComputeRunner, Viewport, ViewportError,
+};
+
+/// Configures how a [`Viewport`] is created: the window, the adapter and device it requests,
+/// and the targets every frame is rendered to.
+///
+/// The backends and power preference can also be overridden without code changes through the
+/// `WGPU_BACKEND` (e.g. `vulkan`, `metal`, `dx12`, `gl`) and `WGPU_POWER_PREF` (`low` or `high`)
+/// environment variables.
+pub struct ViewportBuilder {
+    width: u32,
+    height: u32,
+    title: String,
+    resizable: bool,
+    backends: wgpu::Backends,
+    power_preference: wgpu::PowerPreference,
+    force_fallback_adapter: bool,
+    present_mode: Option<wgpu::PresentMode>,
+    features: wgpu::Features,
+    limits: wgpu::Limits,
+    depth_format: Option<wgpu::TextureFormat>,
+    sample_count: u32,
+    clear_color: Option<wgpu::Color>,
+}
+
+impl ViewportBuilder {
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            title: "rust-gpu experiments".to_string(),
+            resizable: true,
+            // Backends::all => Vulkan + Metal + DX12 + Browser WebGPU
+            backends: wgpu::util::backend_bits_from_env().unwrap_or(wgpu::Backends::all()),
+            power_preference: wgpu::util::power_preference_from_env().unwrap_or_default(),
+            force_fallback_adapter: false,
+            present_mode: None,
+            features: wgpu::Features::empty(),
+            limits: wgpu::Limits::default(),
+            depth_format: None,
+            sample_count: 1,
+            clear_color: None,
+        }
+    }
+
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.title = title.into();
+        self
+    }
+
+    pub fn resizable(mut self, resizable: bool) -> Self {
+        self.resizable = resizable;
+        self
+    }
+
+    /// Backends the adapter may be picked from, e.g. `wgpu::Backends::VULKAN`.
+    pub fn backends(mut self, backends: wgpu::Backends) -> Self {
+        self.backends = backends;
+        self
+    }
+
+    pub fn power_preference(mut self, power_preference: wgpu::PowerPreference) -> Self {
+        self.power_preference = power_preference;
+        self
+    }
+
+    /// Only accept the fallback (software) adapter.
+    pub fn force_fallback_adapter(mut self, force_fallback_adapter: bool) -> Self {
+        self.force_fallback_adapter = force_fallback_adapter;
+        self
+    }
+
+    /// Present mode of the surface, e.g. `Fifo` for vsync, `Mailbox` or `Immediate`. Defaults to
+    /// the surface's preferred mode. Ignored for headless viewports.
+    pub fn present_mode(mut self, present_mode: wgpu::PresentMode) -> Self {
+        self.present_mode = Some(present_mode);
+        self
+    }
+
+    /// Features required from the device, on top of `SPIRV_SHADER_PASSTHROUGH` which is always
+    /// required to load the rust-gpu shaders.
+    pub fn features(mut self, features: wgpu::Features) -> Self {
+        self.features = features;
+        self
+    }
+
+    pub fn limits(mut self, limits: wgpu::Limits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// See [`Viewport::set_depth_format`].
+    pub fn depth_format(mut self, depth_format: wgpu::TextureFormat) -> Self {
+        self.depth_format = Some(depth_format);
+        self
+    }
+
+    /// See [`Viewport::set_sample_count`].
+    pub fn sample_count(mut self, sample_count: u32) -> Self {
+        self.sample_count = sample_count;
+        self
+    }
+
+    /// See [`Viewport::set_clear_color`].
+    pub fn clear_color(mut self, clear_color: wgpu::Color) -> Self {
+        self.clear_color = Some(clear_color);
+        self
+    }
+
+    fn create_instance(&self) -> wgpu::Instance {
+        // The instance is a handle to our GPU
+        wgpu::Instance::new(wgpu::InstanceDescriptor {
+            backends: self.backends,
+            dx12_shader_compiler: Default::default(),
+        })
+    }
+
+    async fn request_adapter(
+        &self,
+        instance: &wgpu::Instance,
+        compatible_surface: Option<&wgpu::Surface>,
+    ) -> Result<wgpu::Adapter, ViewportError> {
+        instance
+            .request_adapter(&wgpu::RequestAdapterOptions {
+                power_preference: self.power_preference,
+                compatible_surface,
+                force_fallback_adapter: self.force_fallback_adapter,
+            })
+            .await
+            .ok_or(ViewportError::NoAdapter {
+                backends: self.backends,
+                force_fallback_adapter: self.force_fallback_adapter,
+            })
+    }
+
+    async fn request_device(
+        &self,
+        adapter: &wgpu::Adapter,
+    ) -> Result<(wgpu::Device, wgpu::Queue), ViewportError> {
+        let features = self.features | wgpu::Features::SPIRV_SHADER_PASSTHROUGH;
+        let missing_features = features - adapter.features();
+        if !missing_features.is_empty() {
+            return Err(ViewportError::UnsupportedFeatures {
+                adapter: adapter.get_info().name,
+                missing: missing_features,
+            });
+        }
+
+        let mut unsupported_limits = vec![];
+        self.limits
+            .check_limits_with_fail_fn(&adapter.limits(), false, |name, requested, allowed| {
+                unsupported_limits.push(format!("{name} (requested {requested}, allowed {allowed})"))
+            });
+        if !unsupported_limits.is_empty() {
+            return Err(ViewportError::UnsupportedLimits {
+                adapter: adapter.get_info().name,
+                limits: unsupported_limits,
+            });
+        }
+
+        Ok(adapter
+            .request_device(
+                &wgpu::DeviceDescriptor {
+                    features,
+                    limits: self.limits.clone(),
+                    label: None,
+                },
+                None, // Trace path
+            )
+            .await?)
+    }
+
+    // Creating some of the wgpu types requires async code
+    pub async fn build(self, event_loop: &EventLoop<()>) -> Result<Viewport, ViewportError> {
+        let window = WindowBuilder::new()
+            .with_title(&self.title)
+            .with_resizable(self.resizable)
+            .with_inner_size(PhysicalSize::new(self.width, self.height))
+            .build(event_loop)?;
+
+        let size = window.inner_size();
+
+        let instance = self.create_instance();
+
+        // # Safety
+        //
+        // The surface needs to live as long as the window that created it.
+        // State owns the window so this should be safe.
+        let surface = unsafe { instance.create_surface(&window) }?;
+
+        let adapter = self.request_adapter(&instance, Some(&surface)).await?;
+
+        let (device, queue) = self.request_device(&adapter).await?;
+
+        let surface_caps = surface.get_capabilities(&adapter);
+        // Adapters that can't present to the surface report no capabilities at all
+        if surface_caps.formats.is_empty()
+            || surface_caps.present_modes.is_empty()
+            || surface_caps.alpha_modes.is_empty()
+        {
+            return Err(ViewportError::IncompatibleSurface {
+                adapter: adapter.get_info().name,
+            });
+        }
+        // Shader code in this tutorial assumes an sRGB surface texture. Using a different
+        // one will result all the colors coming out darker. If you want to support non
+        // sRGB surfaces, you'll need to account for that when drawing to the frame.
+        let surface_format = surface_caps
+            .formats
+            .iter()
+            .copied()
+            .find(|f| f.describe().srgb)
+            .unwrap_or(surface_caps.formats[0]);
+        let present_mode = match self.present_mode {
+            None => surface_caps.present_modes[0],
+            // The automatic modes fall back to a supported mode by themselves
+            Some(
+                present_mode @ (wgpu::PresentMode::AutoVsync | wgpu::PresentMode::AutoNoVsync),
+            ) => present_mode,
+            Some(present_mode) if surface_caps.present_modes.contains(&present_mode) => {
+                present_mode
+            }
+            Some(present_mode) => {
+                return Err(ViewportError::UnsupportedPresentMode {
+                    requested: present_mode,
+                    supported: surface_caps.present_modes,
+                })
+            }
+        };
+        let config = wgpu::SurfaceConfiguration {
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            format: surface_format,
+            width: size.width,
+            height: size.height,
+            present_mode,
+            alpha_mode: surface_caps.alpha_modes[0],
+            view_formats: vec![],
+        };
+        surface.configure(&device, &config);
+
+        let target = RenderTarget::Window {
+            surface,
+            config,
+            window,
+        };
+        self.finish(Viewport::from_target(
+            target,
+            adapter,
+            device,
+            queue,
+            size,
+            surface_format,
+        ))
+    }
+
+    /// Build a viewport without a window, which renders into an owned texture instead of a
+    /// surface.
+    pub async fn build_headless(self) -> Result<Viewport, ViewportError> {
+        let size = PhysicalSize::new(self.width, self.height);
+
+        let instance = self.create_instance();
+
+        let adapter = self.request_adapter(&instance, None).await?;
+
+        let (device, queue) = self.request_device(&adapter).await?;
+
+        let texture = Viewport::create_offscreen_texture(&device, size, HEADLESS_FORMAT);
+
+        let target = RenderTarget::Offscreen { texture };
+        self.finish(Viewport::from_target(
+            target,
+            adapter,
+            device,
+            queue,
+            size,
+            HEADLESS_FORMAT,
+        ))
+    }
+
+    /// Request only a device and queue for running compute kernels. The size, window and render
+    /// target settings are ignored.
+    pub async fn build_compute(self) -> Result<ComputeRunner, ViewportError> {
+        let instance = self.create_instance();
+
+        let adapter = self.request_adapter(&instance, None).await?;
+
+        let (device, queue) = self.request_device(&adapter).await?;
+
+        Ok(ComputeRunner::from_device(device, queue))
+    }
+
+    fn finish(self, mut viewport: Viewport) -> Result<Viewport, ViewportError> {
+        viewport.set_sample_count(self.sample_count)?;
+        viewport.set_depth_format(self.depth_format)?;
+        if let Some(clear_color) = self.clear_color {
+            viewport.set_clear_color(clear_color);
+        }
+        Ok(viewport)
+    }
+}
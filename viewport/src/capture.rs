@@ -0,0 +1,153 @@
+use std::fmt;
+
+use image::RgbaImage;
+use winit::dpi::PhysicalSize;
+
+#[derive(Debug)]
+pub enum CaptureError {
+    /// The frame to capture could not be acquired.
+    Surface(wgpu::SurfaceError),
+    /// Only 8 bit RGBA and BGRA color targets can be captured.
+    UnsupportedFormat(wgpu::TextureFormat),
+    /// The buffer holding the frame could not be mapped.
+    Map(wgpu::BufferAsyncError),
+    /// The captured frame could not be encoded or written.
+    Image(image::ImageError),
+}
+
+impl fmt::Display for CaptureError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CaptureError::Surface(e) => write!(f, "Error acquiring frame to capture: {e}"),
+            CaptureError::UnsupportedFormat(format) => {
+                write!(f, "Capturing {format:?} color targets is not supported")
+            }
+            CaptureError::Map(e) => write!(f, "Error mapping capture buffer: {e}"),
+            CaptureError::Image(e) => write!(f, "Error writing captured frame: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for CaptureError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            CaptureError::Surface(e) => Some(e),
+            CaptureError::UnsupportedFormat(_) => None,
+            CaptureError::Map(e) => Some(e),
+            CaptureError::Image(e) => Some(e),
+        }
+    }
+}
+
+impl From<wgpu::SurfaceError> for CaptureError {
+    fn from(e: wgpu::SurfaceError) -> Self {
+        CaptureError::Surface(e)
+    }
+}
+
+impl From<image::ImageError> for CaptureError {
+    fn from(e: image::ImageError) -> Self {
+        CaptureError::Image(e)
+    }
+}
+
+/// Buffer a rendered color target is copied into, so it can be read back on the CPU.
+pub(crate) struct CaptureBuffer {
+    buffer: wgpu::Buffer,
+    size: PhysicalSize<u32>,
+    padded_bytes_per_row: u32,
+    bgra: bool,
+}
+
+impl CaptureBuffer {
+    pub(crate) fn new(
+        device: &wgpu::Device,
+        format: wgpu::TextureFormat,
+        size: PhysicalSize<u32>,
+    ) -> Result<Self, CaptureError> {
+        let bgra = match format {
+            wgpu::TextureFormat::Rgba8Unorm | wgpu::TextureFormat::Rgba8UnormSrgb => false,
+            wgpu::TextureFormat::Bgra8Unorm | wgpu::TextureFormat::Bgra8UnormSrgb => true,
+            _ => return Err(CaptureError::UnsupportedFormat(format)),
+        };
+
+        // Rows copied from a texture into a buffer need to be aligned to 256 bytes
+        let unpadded_bytes_per_row = 4 * size.width;
+        let alignment = wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+        let padded_bytes_per_row = (unpadded_bytes_per_row + alignment - 1) / alignment * alignment;
+
+        let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Capture Buffer"),
+            size: (padded_bytes_per_row * size.height) as wgpu::BufferAddress,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+
+        Ok(Self {
+            buffer,
+            size,
+            padded_bytes_per_row,
+            bgra,
+        })
+    }
+
+    pub(crate) fn copy_from(&self, encoder: &mut wgpu::CommandEncoder, texture: &wgpu::Texture) {
+        encoder.copy_texture_to_buffer(
+            wgpu::ImageCopyTexture {
+                texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+                aspect: wgpu::TextureAspect::All,
+            },
+            wgpu::ImageCopyBuffer {
+                buffer: &self.buffer,
+                layout: wgpu::ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: std::num::NonZeroU32::new(self.padded_bytes_per_row),
+                    rows_per_image: std::num::NonZeroU32::new(self.size.height),
+                },
+            },
+            wgpu::Extent3d {
+                width: self.size.width,
+                height: self.size.height,
+                depth_or_array_layers: 1,
+            },
+        );
+    }
+
+    /// Wait for the copy to finish and read the frame back as RGBA.
+    pub(crate) fn read(self, device: &wgpu::Device) -> Result<RgbaImage, CaptureError> {
+        let slice = self.buffer.slice(..);
+        let (sender, receiver) = std::sync::mpsc::channel();
+        slice.map_async(wgpu::MapMode::Read, move |result| {
+            sender.send(result).ok();
+        });
+        device.poll(wgpu::Maintain::Wait);
+        receiver
+            .recv()
+            .expect("Capture buffer was dropped before being mapped!")
+            .map_err(CaptureError::Map)?;
+
+        // Strip the row padding
+        let unpadded_bytes_per_row = 4 * self.size.width as usize;
+        let mut pixels = Vec::with_capacity(unpadded_bytes_per_row * self.size.height as usize);
+        {
+            let data = slice.get_mapped_range();
+            for row in data.chunks(self.padded_bytes_per_row as usize) {
+                pixels.extend_from_slice(&row[..unpadded_bytes_per_row]);
+            }
+        }
+        self.buffer.unmap();
+
+        // sRGB targets already store gamma encoded values, which is what PNG expects, so only the
+        // channel order needs fixing.
+        if self.bgra {
+            for pixel in pixels.chunks_exact_mut(4) {
+                pixel.swap(0, 2);
+            }
+        }
+
+        Ok(RgbaImage::from_raw(self.size.width, self.size.height, pixels)
+            .expect("Capture buffer has the size of the frame"))
+    }
+}
@@ -0,0 +1,333 @@
+use std::fmt;
+
+use wgpu::util::DeviceExt;
+
+use crate::{ViewportBuilder, ViewportError};
+
+#[derive(Debug)]
+pub enum ComputeError {
+    /// Covering the problem size takes more workgroups in some dimension than the device's
+    /// `max_compute_workgroups_per_dimension`.
+    TooManyWorkgroups {
+        problem_size: [u32; 3],
+        workgroup_count: [u32; 3],
+        max_workgroups: u32,
+    },
+    /// The buffer holding the results could not be mapped.
+    Map(wgpu::BufferAsyncError),
+}
+
+impl fmt::Display for ComputeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ComputeError::TooManyWorkgroups {
+                problem_size,
+                workgroup_count,
+                max_workgroups,
+            } => write!(
+                f,
+                "Problem size {problem_size:?} needs {workgroup_count:?} workgroups, but at most \
+                {max_workgroups} can be dispatched per dimension"
+            ),
+            ComputeError::Map(e) => write!(f, "Error mapping staging buffer: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for ComputeError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ComputeError::TooManyWorkgroups { .. } => None,
+            ComputeError::Map(e) => Some(e),
+        }
+    }
+}
+
+impl From<wgpu::BufferAsyncError> for ComputeError {
+    fn from(e: wgpu::BufferAsyncError) -> Self {
+        ComputeError::Map(e)
+    }
+}
+
+/// How a compute shader accesses a storage buffer binding.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StorageAccess {
+    ReadOnly,
+    ReadWrite,
+}
+
+/// A compute pipeline whose bind group 0 consists of storage buffers, bound in order starting
+/// at binding 0.
+pub struct ComputeKernel {
+    pipeline: wgpu::ComputePipeline,
+    bind_group_layout: wgpu::BindGroupLayout,
+    workgroup_size: [u32; 3],
+    max_workgroups: u32,
+}
+
+impl ComputeKernel {
+    /// `workgroup_size` has to match the `threads(..)` of the entry point, it is used to derive
+    /// the number of workgroups to dispatch from the problem size. Panics if any of its
+    /// dimensions is 0.
+    pub fn new(
+        device: &wgpu::Device,
+        module: &wgpu::ShaderModule,
+        entry_point: &str,
+        workgroup_size: [u32; 3],
+        storage_buffers: &[StorageAccess],
+    ) -> Self {
+        assert!(
+            !workgroup_size.contains(&0),
+            "Workgroup size {workgroup_size:?} has an empty dimension!"
+        );
+        let entries = storage_buffers
+            .iter()
+            .enumerate()
+            .map(|(binding, access)| wgpu::BindGroupLayoutEntry {
+                binding: binding as u32,
+                visibility: wgpu::ShaderStages::COMPUTE,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Storage {
+                        read_only: *access == StorageAccess::ReadOnly,
+                    },
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            })
+            .collect::<Vec<_>>();
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("Compute Bind Group Layout"),
+            entries: &entries,
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Compute Pipeline Layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        let pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: Some("Compute Pipeline"),
+            layout: Some(&pipeline_layout),
+            module,
+            entry_point,
+        });
+
+        Self {
+            pipeline,
+            bind_group_layout,
+            workgroup_size,
+            max_workgroups: device.limits().max_compute_workgroups_per_dimension,
+        }
+    }
+
+    pub fn bind_group_layout(&self) -> &wgpu::BindGroupLayout {
+        &self.bind_group_layout
+    }
+
+    /// Bind the given storage buffers, in the order they were declared in [`ComputeKernel::new`].
+    pub fn bind(&self, device: &wgpu::Device, buffers: &[&wgpu::Buffer]) -> wgpu::BindGroup {
+        let entries = buffers
+            .iter()
+            .enumerate()
+            .map(|(binding, buffer)| wgpu::BindGroupEntry {
+                binding: binding as u32,
+                resource: buffer.as_entire_binding(),
+            })
+            .collect::<Vec<_>>();
+        device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Compute Bind Group"),
+            layout: &self.bind_group_layout,
+            entries: &entries,
+        })
+    }
+
+    /// Number of workgroups needed to cover `problem_size` invocations in every dimension. The
+    /// shader is responsible for ignoring the invocations past the end of the problem.
+    pub fn workgroup_count(&self, problem_size: [u32; 3]) -> Result<[u32; 3], ComputeError> {
+        workgroup_count(self.workgroup_size, problem_size, self.max_workgroups)
+    }
+
+    /// Record a dispatch covering `problem_size` invocations into the compute pass.
+    pub fn dispatch<'a>(
+        &'a self,
+        compute_pass: &mut wgpu::ComputePass<'a>,
+        bind_group: &'a wgpu::BindGroup,
+        problem_size: [u32; 3],
+    ) -> Result<(), ComputeError> {
+        let [x, y, z] = self.workgroup_count(problem_size)?;
+        compute_pass.set_pipeline(&self.pipeline);
+        compute_pass.set_bind_group(0, bind_group, &[]);
+        compute_pass.dispatch_workgroups(x, y, z);
+        Ok(())
+    }
+}
+
+/// Runs compute kernels without a window or render target, and reads their results back.
+pub struct ComputeRunner {
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+}
+
+impl ComputeRunner {
+    /// Create a runner on the default adapter, or on the fallback (software) adapter. Use
+    /// [`ViewportBuilder::build_compute`] for more control over the adapter and device.
+    pub async fn new(force_fallback_adapter: bool) -> Result<Self, ViewportError> {
+        ViewportBuilder::new(0, 0)
+            .force_fallback_adapter(force_fallback_adapter)
+            .build_compute()
+            .await
+    }
+
+    pub(crate) fn from_device(device: wgpu::Device, queue: wgpu::Queue) -> Self {
+        Self { device, queue }
+    }
+
+    pub fn device(&self) -> &wgpu::Device {
+        &self.device
+    }
+
+    pub fn queue(&self) -> &wgpu::Queue {
+        &self.queue
+    }
+
+    /// Create a storage buffer initialized with `contents`, which can be read back with
+    /// [`ComputeRunner::read_buffer`]. Panics if `contents` is empty, as empty buffers can't be
+    /// bound.
+    pub fn create_storage_buffer<T: bytemuck::Pod>(
+        &self,
+        label: &str,
+        contents: &[T],
+    ) -> wgpu::Buffer {
+        assert!(
+            !contents.is_empty(),
+            "Storage buffer '{label}' would be empty!"
+        );
+        self.device
+            .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some(label),
+                contents: bytemuck::cast_slice(contents),
+                usage: wgpu::BufferUsages::STORAGE
+                    | wgpu::BufferUsages::COPY_SRC
+                    | wgpu::BufferUsages::COPY_DST,
+            })
+    }
+
+    /// Dispatch the kernel over `problem_size` invocations with the given storage buffers bound,
+    /// without waiting for it to finish. Nothing is submitted if the problem takes more
+    /// workgroups than the device can dispatch at once.
+    pub fn dispatch(
+        &self,
+        kernel: &ComputeKernel,
+        buffers: &[&wgpu::Buffer],
+        problem_size: [u32; 3],
+    ) -> Result<(), ComputeError> {
+        kernel.workgroup_count(problem_size)?;
+        let bind_group = kernel.bind(&self.device, buffers);
+        let mut encoder = self
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("Compute Encoder"),
+            });
+        {
+            let mut compute_pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+                label: Some("Compute Pass"),
+            });
+            kernel.dispatch(&mut compute_pass, &bind_group, problem_size)?;
+        }
+        self.queue.submit(std::iter::once(encoder.finish()));
+        Ok(())
+    }
+
+    /// Wait for all submitted work to finish and read the contents of a storage buffer back.
+    pub fn read_buffer<T: bytemuck::Pod>(
+        &self,
+        buffer: &wgpu::Buffer,
+    ) -> Result<Vec<T>, ComputeError> {
+        let staging_buffer = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Staging Buffer"),
+            size: buffer.size(),
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+        let mut encoder = self
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("Readback Encoder"),
+            });
+        encoder.copy_buffer_to_buffer(buffer, 0, &staging_buffer, 0, buffer.size());
+        self.queue.submit(std::iter::once(encoder.finish()));
+
+        let slice = staging_buffer.slice(..);
+        let (sender, receiver) = std::sync::mpsc::channel();
+        slice.map_async(wgpu::MapMode::Read, move |result| {
+            sender.send(result).ok();
+        });
+        self.device.poll(wgpu::Maintain::Wait);
+        receiver
+            .recv()
+            .expect("Staging buffer was dropped before being mapped!")?;
+
+        let result = bytemuck::cast_slice(&slice.get_mapped_range()).to_vec();
+        staging_buffer.unmap();
+
+        Ok(result)
+    }
+}
+
+fn workgroup_count(
+    workgroup_size: [u32; 3],
+    problem_size: [u32; 3],
+    max_workgroups: u32,
+) -> Result<[u32; 3], ComputeError> {
+    // Rounds up without overflowing for problem sizes close to u32::MAX
+    let workgroup_count = std::array::from_fn(|i| {
+        problem_size[i]
+            .checked_sub(1)
+            .map_or(0, |last| last / workgroup_size[i] + 1)
+    });
+    if workgroup_count.iter().any(|&count| count > max_workgroups) {
+        return Err(ComputeError::TooManyWorkgroups {
+            problem_size,
+            workgroup_count,
+            max_workgroups,
+        });
+    }
+    Ok(workgroup_count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn workgroup_counts_round_up() {
+        assert_eq!(
+            workgroup_count([64, 8, 1], [1000, 8, 3], 65535).unwrap(),
+            [16, 1, 3]
+        );
+        assert_eq!(
+            workgroup_count([64, 1, 1], [65535 * 64, 1, 1], 65535).unwrap(),
+            [65535, 1, 1]
+        );
+    }
+
+    #[test]
+    fn problems_above_the_workgroup_limit_are_rejected() {
+        let default_limit = wgpu::Limits::default().max_compute_workgroups_per_dimension;
+        assert!(matches!(
+            workgroup_count([64, 1, 1], [default_limit * 64 + 1, 1, 1], default_limit),
+            Err(ComputeError::TooManyWorkgroups {
+                workgroup_count: [count, 1, 1],
+                ..
+            }) if count == default_limit + 1
+        ));
+        assert!(matches!(
+            workgroup_count([1, 1, 1], [1, u32::MAX, 1], default_limit),
+            Err(ComputeError::TooManyWorkgroups {
+                workgroup_count: [1, u32::MAX, 1],
+                ..
+            })
+        ));
+    }
+}
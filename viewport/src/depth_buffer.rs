@@ -0,0 +1,93 @@
+use winit::dpi::PhysicalSize;
+
+/// Depth (and optionally stencil) texture owned by a viewport, matching its size.
+pub(crate) struct DepthBuffer {
+    format: wgpu::TextureFormat,
+    sample_count: u32,
+    view: wgpu::TextureView,
+}
+
+impl DepthBuffer {
+    pub(crate) fn new(
+        device: &wgpu::Device,
+        format: wgpu::TextureFormat,
+        size: PhysicalSize<u32>,
+        sample_count: u32,
+    ) -> Self {
+        assert!(
+            is_depth_stencil(format),
+            "{format:?} is not a depth or stencil format!"
+        );
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Depth Texture"),
+            size: wgpu::Extent3d {
+                width: size.width,
+                height: size.height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            view_formats: &[],
+        });
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+        Self {
+            format,
+            sample_count,
+            view,
+        }
+    }
+
+    pub(crate) fn format(&self) -> wgpu::TextureFormat {
+        self.format
+    }
+
+    pub(crate) fn sample_count(&self) -> u32 {
+        self.sample_count
+    }
+
+    pub(crate) fn attachment(
+        &self,
+        depth_clear_value: f32,
+        stencil_clear_value: u32,
+    ) -> wgpu::RenderPassDepthStencilAttachment {
+        wgpu::RenderPassDepthStencilAttachment {
+            view: &self.view,
+            depth_ops: has_depth(self.format).then_some(wgpu::Operations {
+                load: wgpu::LoadOp::Clear(depth_clear_value),
+                store: true,
+            }),
+            stencil_ops: has_stencil(self.format).then_some(wgpu::Operations {
+                load: wgpu::LoadOp::Clear(stencil_clear_value),
+                store: true,
+            }),
+        }
+    }
+}
+
+pub(crate) fn is_depth_stencil(format: wgpu::TextureFormat) -> bool {
+    has_depth(format) || has_stencil(format)
+}
+
+fn has_depth(format: wgpu::TextureFormat) -> bool {
+    matches!(
+        format,
+        wgpu::TextureFormat::Depth16Unorm
+            | wgpu::TextureFormat::Depth24Plus
+            | wgpu::TextureFormat::Depth24PlusStencil8
+            | wgpu::TextureFormat::Depth32Float
+            | wgpu::TextureFormat::Depth32FloatStencil8
+    )
+}
+
+fn has_stencil(format: wgpu::TextureFormat) -> bool {
+    matches!(
+        format,
+        wgpu::TextureFormat::Stencil8
+            | wgpu::TextureFormat::Depth24PlusStencil8
+            | wgpu::TextureFormat::Depth32FloatStencil8
+    )
+}
@@ -0,0 +1,44 @@
+use std::time::Duration;
+
+use winit::{dpi::PhysicalSize, event::WindowEvent};
+
+use crate::{FrameInfo, ShaderInterface, ShaderInterfaceError, Viewport};
+
+/// The hooks a viewport calls on every dresser, whether it is a [`RenderPassDresser`] or a
+/// [`FrameDresser`]. Every hook does nothing by default.
+///
+/// [`RenderPassDresser`]: crate::RenderPassDresser
+/// [`FrameDresser`]: crate::FrameDresser
+pub trait DresserHooks {
+    /// Called before every frame is rendered, e.g. to upload uniforms.
+    fn prepare(&mut self, _device: &wgpu::Device, _queue: &wgpu::Queue, _frame_info: &FrameInfo) {}
+
+    /// Called after the viewport is resized, to recreate size dependent resources.
+    fn resize(&mut self, _device: &wgpu::Device, _new_size: PhysicalSize<u32>) {}
+
+    /// Return `true` if the event was consumed, in which case the viewport ignores it.
+    fn input(&mut self, _event: &WindowEvent) -> bool {
+        false
+    }
+
+    /// Called once per frame with the time since the previous update.
+    fn update(&mut self, _dt: Duration) {}
+
+    /// Rebuild the pipeline using `shader` from a freshly loaded module, see
+    /// [`Viewport::watch_shader`]. The pipeline should be checked against the module's
+    /// `interface` before it is created, see [`ShaderInterface::validate_render_pipeline`].
+    /// Return `None` if this dresser doesn't use the shader.
+    fn reload_pipeline(
+        &self,
+        _viewport: &Viewport,
+        _shader: &str,
+        _module: &wgpu::ShaderModule,
+        _interface: &ShaderInterface,
+    ) -> Option<Result<wgpu::RenderPipeline, ShaderInterfaceError>> {
+        None
+    }
+
+    /// Start using a pipeline returned by [`DresserHooks::reload_pipeline`], once it passed
+    /// validation.
+    fn replace_pipeline(&mut self, _shader: &str, _pipeline: wgpu::RenderPipeline) {}
+}
@@ -0,0 +1,129 @@
+use std::fmt;
+
+#[derive(Debug)]
+pub enum ViewportError {
+    /// The window could not be created.
+    Window(winit::error::OsError),
+    /// The surface could not be created for the window.
+    CreateSurface(wgpu::CreateSurfaceError),
+    /// No adapter matches the requested backends and options.
+    NoAdapter {
+        backends: wgpu::Backends,
+        force_fallback_adapter: bool,
+    },
+    /// The adapter lacks some of the requested features.
+    UnsupportedFeatures {
+        adapter: String,
+        missing: wgpu::Features,
+    },
+    /// The adapter does not allow some of the requested limits.
+    UnsupportedLimits {
+        adapter: String,
+        /// Description of every limit exceeding what the adapter allows.
+        limits: Vec<String>,
+    },
+    /// The adapter can't present to the window's surface.
+    IncompatibleSurface { adapter: String },
+    /// The surface does not support the requested present mode.
+    UnsupportedPresentMode {
+        requested: wgpu::PresentMode,
+        supported: Vec<wgpu::PresentMode>,
+    },
+    /// The format is not a depth or stencil format, but was requested for the depth buffer.
+    NotADepthFormat(wgpu::TextureFormat),
+    /// The format needs device features that were not requested.
+    UnsupportedFormat {
+        format: wgpu::TextureFormat,
+        missing: wgpu::Features,
+    },
+    /// Targets of the format can't be rendered to (and resolved) with the sample count.
+    UnsupportedSampleCount {
+        format: wgpu::TextureFormat,
+        sample_count: u32,
+    },
+    /// The device could not be created.
+    RequestDevice(wgpu::RequestDeviceError),
+    /// The next frame could not be acquired from the surface.
+    SurfaceAcquire(wgpu::SurfaceError),
+}
+
+impl fmt::Display for ViewportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ViewportError::Window(e) => write!(f, "Error creating window: {e}"),
+            ViewportError::CreateSurface(e) => write!(f, "Error creating surface: {e}"),
+            ViewportError::NoAdapter {
+                backends,
+                force_fallback_adapter,
+            } => write!(
+                f,
+                "No adapter found for backends {backends:?} \
+                (force fallback adapter: {force_fallback_adapter})"
+            ),
+            ViewportError::UnsupportedFeatures { adapter, missing } => {
+                write!(f, "Adapter {adapter} does not support features {missing:?}")
+            }
+            ViewportError::UnsupportedLimits { adapter, limits } => {
+                write!(f, "Adapter {adapter} does not support limits {}", limits.join(", "))
+            }
+            ViewportError::IncompatibleSurface { adapter } => {
+                write!(f, "Adapter {adapter} can't present to the window's surface")
+            }
+            ViewportError::UnsupportedPresentMode {
+                requested,
+                supported,
+            } => write!(
+                f,
+                "Present mode {requested:?} is not supported, supported modes are {supported:?}"
+            ),
+            ViewportError::NotADepthFormat(format) => {
+                write!(f, "{format:?} is not a depth or stencil format")
+            }
+            ViewportError::UnsupportedFormat { format, missing } => {
+                write!(f, "{format:?} requires the features {missing:?}, which were not requested")
+            }
+            ViewportError::UnsupportedSampleCount {
+                format,
+                sample_count,
+            } => write!(f, "{format:?} does not support a sample count of {sample_count}"),
+            ViewportError::RequestDevice(e) => write!(f, "Error requesting device: {e}"),
+            ViewportError::SurfaceAcquire(e) => write!(f, "Error acquiring frame: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for ViewportError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ViewportError::Window(e) => Some(e),
+            ViewportError::CreateSurface(e) => Some(e),
+            ViewportError::RequestDevice(e) => Some(e),
+            ViewportError::SurfaceAcquire(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<winit::error::OsError> for ViewportError {
+    fn from(e: winit::error::OsError) -> Self {
+        ViewportError::Window(e)
+    }
+}
+
+impl From<wgpu::CreateSurfaceError> for ViewportError {
+    fn from(e: wgpu::CreateSurfaceError) -> Self {
+        ViewportError::CreateSurface(e)
+    }
+}
+
+impl From<wgpu::RequestDeviceError> for ViewportError {
+    fn from(e: wgpu::RequestDeviceError) -> Self {
+        ViewportError::RequestDevice(e)
+    }
+}
+
+impl From<wgpu::SurfaceError> for ViewportError {
+    fn from(e: wgpu::SurfaceError) -> Self {
+        ViewportError::SurfaceAcquire(e)
+    }
+}
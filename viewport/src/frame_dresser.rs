@@ -0,0 +1,20 @@
+use crate::{DresserHooks, RenderPassDresser, Renderer};
+
+/// Records whole frames, for dressers that need more than the single render pass a
+/// [`RenderPassDresser`] gets, e.g. compute passes, offscreen render passes or post-processing.
+///
+/// Every [`RenderPassDresser`] is a `FrameDresser` which records its one render pass.
+pub trait FrameDresser: DresserHooks {
+    /// Record the frame into the renderer's command encoder. The frame is expected to end up in
+    /// [`Renderer::view`], typically through a final [`Renderer::render_pass`].
+    fn dress_frame(&mut self, renderer: &mut Renderer);
+}
+
+impl<T: RenderPassDresser> FrameDresser for T {
+    fn dress_frame(&mut self, renderer: &mut Renderer) {
+        let load_op = self
+            .color_load_op()
+            .unwrap_or(renderer.viewport().color_load_op());
+        self.dress(renderer.render_pass(load_op));
+    }
+}
@@ -0,0 +1,123 @@
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+use winit::dpi::PhysicalSize;
+
+/// Number of frames the rolling averages are taken over.
+const AVERAGE_FRAMES: usize = 60;
+
+/// Information about the frame that is about to be rendered.
+#[derive(Clone, Copy, Debug)]
+pub struct FrameInfo {
+    /// Size of the color target in pixels.
+    pub size: PhysicalSize<u32>,
+    /// Index of this frame, starting at 0.
+    pub frame_index: u64,
+    /// Time since the previous frame, zero for the first frame.
+    pub delta: Duration,
+    /// Time since the first frame.
+    pub elapsed: Duration,
+    /// Frame time averaged over the last 60 frames.
+    pub average_frame_time: Duration,
+    /// Frames per second averaged over the last 60 frames.
+    pub fps: f32,
+}
+
+/// Keeps track of frame timings for a viewport.
+pub(crate) struct FrameTimer {
+    first_frame: Option<Instant>,
+    last_frame: Option<Instant>,
+    frame_index: u64,
+    delta: Duration,
+    frame_times: VecDeque<Duration>,
+    frame_time_sum: Duration,
+    log_interval: Option<Duration>,
+    last_log: Option<Instant>,
+}
+
+impl FrameTimer {
+    pub(crate) fn new(log_interval: Option<Duration>) -> Self {
+        Self {
+            first_frame: None,
+            last_frame: None,
+            frame_index: 0,
+            delta: Duration::ZERO,
+            frame_times: VecDeque::with_capacity(AVERAGE_FRAMES),
+            frame_time_sum: Duration::ZERO,
+            log_interval,
+            last_log: None,
+        }
+    }
+
+    /// Start a new frame and return the time since the previous one.
+    pub(crate) fn tick(&mut self) -> Duration {
+        let now = Instant::now();
+        match self.last_frame {
+            Some(last_frame) => {
+                self.frame_index += 1;
+                self.delta = now - last_frame;
+                if self.frame_times.len() == AVERAGE_FRAMES {
+                    self.frame_time_sum -= self.frame_times.pop_front().unwrap();
+                }
+                self.frame_times.push_back(self.delta);
+                self.frame_time_sum += self.delta;
+            }
+            None => {
+                self.first_frame = Some(now);
+                self.last_log = Some(now);
+            }
+        }
+        self.last_frame = Some(now);
+
+        if let (Some(interval), Some(last_log)) = (self.log_interval, self.last_log) {
+            if now - last_log >= interval {
+                self.last_log = Some(now);
+                log::info!(
+                    "Frame {}: {:.1} fps ({:.2} ms/frame)",
+                    self.frame_index,
+                    self.fps(),
+                    self.average_frame_time().as_secs_f64() * 1000.
+                );
+            }
+        }
+
+        self.delta
+    }
+
+    pub(crate) fn set_log_interval(&mut self, log_interval: Option<Duration>) {
+        self.log_interval = log_interval;
+    }
+
+    fn average_frame_time(&self) -> Duration {
+        if self.frame_times.is_empty() {
+            Duration::ZERO
+        } else {
+            self.frame_time_sum / self.frame_times.len() as u32
+        }
+    }
+
+    fn fps(&self) -> f32 {
+        let average_frame_time = self.average_frame_time().as_secs_f32();
+        if average_frame_time > 0. {
+            1. / average_frame_time
+        } else {
+            0.
+        }
+    }
+
+    pub(crate) fn frame_info(&self, size: PhysicalSize<u32>) -> FrameInfo {
+        FrameInfo {
+            size,
+            frame_index: self.frame_index,
+            delta: self.delta,
+            elapsed: match (self.first_frame, self.last_frame) {
+                (Some(first_frame), Some(last_frame)) => last_frame - first_frame,
+                _ => Duration::ZERO,
+            },
+            average_frame_time: self.average_frame_time(),
+            fps: self.fps(),
+        }
+    }
+}
@@ -0,0 +1,150 @@
+use std::{
+    fmt, fs, io,
+    path::{Path, PathBuf},
+    time::{Duration, Instant, SystemTime},
+};
+
+use naga::valid::{Capabilities, ValidationFlags, Validator};
+
+use crate::{ShaderInterface, ShaderInterfaceError};
+
+/// How often the watched files are checked for changes.
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+const SPIRV_MAGIC_NUMBER: u32 = 0x0723_0203;
+
+#[derive(Debug)]
+pub(crate) enum ShaderReloadError {
+    Io(io::Error),
+    /// The file is not a SPIR-V module, e.g. because it is still being written.
+    InvalidSpirv,
+    /// The module could not be parsed.
+    Parse(naga::front::spv::Error),
+    /// The module failed naga's validation, or creating the dresser's pipeline from it failed
+    /// wgpu's validation.
+    Validation(Box<dyn std::error::Error>),
+    /// The dresser's pipeline does not match the interface of the module.
+    Interface(ShaderInterfaceError),
+    /// The dresser did not rebuild any pipeline from the module.
+    NotReloaded,
+}
+
+impl fmt::Display for ShaderReloadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ShaderReloadError::Io(e) => write!(f, "Error reading shader: {e}"),
+            ShaderReloadError::InvalidSpirv => write!(f, "Not a SPIR-V module"),
+            ShaderReloadError::Parse(e) => write!(f, "Error parsing SPIR-V module: {e}"),
+            ShaderReloadError::Validation(e) => write!(f, "Validation failed: {e}"),
+            ShaderReloadError::Interface(e) => write!(f, "{e}"),
+            ShaderReloadError::NotReloaded => {
+                write!(f, "The dresser does not rebuild its pipeline for this shader")
+            }
+        }
+    }
+}
+
+impl From<io::Error> for ShaderReloadError {
+    fn from(e: io::Error) -> Self {
+        ShaderReloadError::Io(e)
+    }
+}
+
+/// Read a SPIR-V module from disk, checking it well enough for
+/// [`wgpu::util::make_spirv_raw`] not to panic.
+pub(crate) fn read_spirv(path: &Path) -> Result<Vec<u8>, ShaderReloadError> {
+    let bytes = fs::read(path)?;
+    let magic_number = bytes
+        .get(..4)
+        .map(|magic_number| u32::from_le_bytes(magic_number.try_into().unwrap()));
+    match magic_number {
+        Some(magic_number)
+            if bytes.len() % 4 == 0
+                && (magic_number == SPIRV_MAGIC_NUMBER
+                    || magic_number == SPIRV_MAGIC_NUMBER.swap_bytes()) =>
+        {
+            Ok(bytes)
+        }
+        _ => Err(ShaderReloadError::InvalidSpirv),
+    }
+}
+
+/// Parse and validate a module with naga. wgpu does neither for SPIR-V passthrough, so broken
+/// modules would otherwise go straight to the driver. The interface of the module is returned
+/// for the dresser to check its pipeline against.
+pub(crate) fn validate_spirv(spirv: &[u8]) -> Result<ShaderInterface, ShaderReloadError> {
+    let module = naga::front::spv::parse_u8_slice(spirv, &Default::default())
+        .map_err(ShaderReloadError::Parse)?;
+    let info = Validator::new(ValidationFlags::all(), Capabilities::all())
+        .validate(&module)
+        .map_err(|e| ShaderReloadError::Validation(Box::new(e)))?;
+    Ok(ShaderInterface::from_module(module, info))
+}
+
+struct WatchedShader {
+    name: String,
+    path: PathBuf,
+    modified: Option<SystemTime>,
+}
+
+/// Polls the modification times of SPIR-V modules on disk.
+#[derive(Default)]
+pub(crate) struct ShaderWatcher {
+    shaders: Vec<WatchedShader>,
+    last_poll: Option<Instant>,
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|metadata| metadata.modified()).ok()
+}
+
+impl ShaderWatcher {
+    pub(crate) fn watch(&mut self, name: String, path: PathBuf) {
+        let modified = modified(&path);
+        self.shaders.push(WatchedShader {
+            name,
+            path,
+            modified,
+        });
+    }
+
+    /// Names and paths of the shaders that changed since the previous poll.
+    pub(crate) fn poll(&mut self) -> Vec<(String, PathBuf)> {
+        if self.shaders.is_empty()
+            || matches!(self.last_poll, Some(last_poll) if last_poll.elapsed() < POLL_INTERVAL)
+        {
+            return vec![];
+        }
+        self.last_poll = Some(Instant::now());
+
+        let mut changed = vec![];
+        for shader in self.shaders.iter_mut() {
+            let modified = modified(&shader.path);
+            if modified.is_some() && modified != shader.modified {
+                shader.modified = modified;
+                changed.push((shader.name.clone(), shader.path.clone()));
+            }
+        }
+        changed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn malformed_modules_are_rejected() {
+        // A valid header (magic number, version 1.0, generator, bound, schema) followed by an
+        // instruction whose word count runs past the end of the module
+        let words: [u32; 6] = [SPIRV_MAGIC_NUMBER, 0x0001_0000, 0, 1, 0, 0x0005_0047];
+        let bytes = words
+            .iter()
+            .flat_map(|word| word.to_le_bytes())
+            .collect::<Vec<_>>();
+        assert!(matches!(
+            validate_spirv(&bytes),
+            Err(ShaderReloadError::Parse(_))
+        ));
+    }
+}
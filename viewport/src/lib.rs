@@ -1,7 +1,25 @@
 mod viewport;
+mod builder;
 mod render_pass_dresser;
+mod frame_dresser;
+mod dresser_hooks;
+mod capture;
+mod frame_info;
+mod depth_buffer;
+mod error;
+mod compute;
+mod hot_reload;
+mod shader_interface;
 
-pub use viewport::Viewport;
+pub use viewport::{Renderer, Viewport};
+pub use builder::ViewportBuilder;
 pub use render_pass_dresser::RenderPassDresser;
+pub use frame_dresser::FrameDresser;
+pub use dresser_hooks::DresserHooks;
+pub use capture::CaptureError;
+pub use frame_info::FrameInfo;
+pub use error::ViewportError;
+pub use compute::{ComputeError, ComputeKernel, ComputeRunner, StorageAccess};
+pub use shader_interface::{ShaderInterface, ShaderInterfaceError};
 
 
@@ -1,3 +1,11 @@
-pub trait RenderPassDresser {
+use crate::DresserHooks;
+
+pub trait RenderPassDresser: DresserHooks {
+    /// Override the viewport's load operation of the color target for the next frame, e.g.
+    /// `LoadOp::Load` to keep the previous frame or skip a clear that is fully overwritten.
+    fn color_load_op(&self) -> Option<wgpu::LoadOp<wgpu::Color>> {
+        None
+    }
+
     fn dress<'a, 'b>(&'a self, render_pass: wgpu::RenderPass<'b>) where 'a: 'b;
-}
\ No newline at end of file
+}
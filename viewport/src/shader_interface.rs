@@ -0,0 +1,719 @@
+use std::fmt;
+
+use naga::{
+    valid::{Capabilities, ModuleInfo, ValidationFlags, Validator},
+    AddressSpace, Binding, Handle, ImageClass, ImageDimension, Module, ScalarKind, ShaderStage,
+    StorageAccess, Type, TypeInner,
+};
+use naga_names::{stage_name, type_name};
+
+#[derive(Debug)]
+pub enum ShaderInterfaceError {
+    /// The SPIR-V module could not be parsed.
+    Parse(naga::front::spv::Error),
+    /// The pipeline doesn't match the interface of the module, one description per mismatch.
+    Mismatches(Vec<String>),
+}
+
+impl fmt::Display for ShaderInterfaceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ShaderInterfaceError::Parse(e) => write!(f, "Error parsing SPIR-V module: {e}"),
+            ShaderInterfaceError::Mismatches(mismatches) => {
+                write!(f, "Pipeline does not match the shader interface:")?;
+                for mismatch in mismatches {
+                    write!(f, "\n  - {mismatch}")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for ShaderInterfaceError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ShaderInterfaceError::Parse(e) => Some(e),
+            ShaderInterfaceError::Mismatches(_) => None,
+        }
+    }
+}
+
+/// The inputs, outputs and bindings a SPIR-V module declares, to check pipelines against before
+/// creating them. With SPIR-V passthrough, mismatches otherwise only show up as driver errors or
+/// garbage on screen.
+pub struct ShaderInterface {
+    module: Module,
+    // Only needed to find out which globals an entry point uses, `None` if the analysis failed
+    info: Option<ModuleInfo>,
+}
+
+impl ShaderInterface {
+    /// Parse a module, e.g. the `source` of `wgpu::include_spirv_raw!`.
+    pub fn from_spirv(spirv: &[u32]) -> Result<Self, ShaderInterfaceError> {
+        let module = naga::front::spv::Parser::new(spirv.iter().copied(), &Default::default())
+            .parse()
+            .map_err(ShaderInterfaceError::Parse)?;
+        let info = Validator::new(ValidationFlags::empty(), Capabilities::all())
+            .validate(&module)
+            .ok();
+        Ok(Self { module, info })
+    }
+
+    /// Wrap a module that already passed naga's validation.
+    pub(crate) fn from_module(module: Module, info: ModuleInfo) -> Self {
+        Self {
+            module,
+            info: Some(info),
+        }
+    }
+
+    /// Check the vertex buffer layouts, color targets and bind group layouts of a render pipeline
+    /// against the entry points it uses. `bind_group_layouts` holds the entries of every bind
+    /// group layout in the pipeline layout, in order.
+    ///
+    /// This is stricter than wgpu in one way: vertex attributes have to provide exactly as many
+    /// components as the shader input they feed, as anything else is almost always a mistake.
+    pub fn validate_render_pipeline(
+        &self,
+        descriptor: &wgpu::RenderPipelineDescriptor,
+        bind_group_layouts: &[&[wgpu::BindGroupLayoutEntry]],
+    ) -> Result<(), ShaderInterfaceError> {
+        self.check_render_pipeline(
+            (descriptor.vertex.entry_point, descriptor.vertex.buffers),
+            descriptor
+                .fragment
+                .as_ref()
+                .map(|fragment| (fragment.entry_point, fragment.targets)),
+            bind_group_layouts,
+        )
+    }
+
+    /// [`ShaderInterface::validate_render_pipeline`] on the parts of the descriptor it checks,
+    /// which don't need a device to create.
+    fn check_render_pipeline(
+        &self,
+        (vertex_entry_point, buffers): (&str, &[wgpu::VertexBufferLayout]),
+        fragment: Option<(&str, &[Option<wgpu::ColorTargetState>])>,
+        bind_group_layouts: &[&[wgpu::BindGroupLayoutEntry]],
+    ) -> Result<(), ShaderInterfaceError> {
+        let mut mismatches = vec![];
+
+        match self.entry_point(vertex_entry_point, ShaderStage::Vertex) {
+            Ok(index) => {
+                self.check_vertex_inputs(index, buffers, &mut mismatches);
+                self.check_bindings(index, bind_group_layouts, &mut mismatches);
+            }
+            Err(mismatch) => mismatches.push(mismatch),
+        }
+
+        if let Some((fragment_entry_point, targets)) = fragment {
+            match self.entry_point(fragment_entry_point, ShaderStage::Fragment) {
+                Ok(index) => {
+                    self.check_fragment_outputs(index, targets, &mut mismatches);
+                    self.check_bindings(index, bind_group_layouts, &mut mismatches);
+                }
+                Err(mismatch) => mismatches.push(mismatch),
+            }
+        }
+
+        if mismatches.is_empty() {
+            Ok(())
+        } else {
+            Err(ShaderInterfaceError::Mismatches(mismatches))
+        }
+    }
+
+    fn entry_point(&self, name: &str, stage: ShaderStage) -> Result<usize, String> {
+        let stage_name = stage_name(stage);
+        self.module
+            .entry_points
+            .iter()
+            .position(|entry_point| entry_point.name == name && entry_point.stage == stage)
+            .ok_or_else(|| {
+                let available = self
+                    .module
+                    .entry_points
+                    .iter()
+                    .filter(|entry_point| entry_point.stage == stage)
+                    .map(|entry_point| entry_point.name.as_str())
+                    .collect::<Vec<_>>();
+                format!(
+                    "The module has no {stage_name} entry point named '{name}' \
+                    (available: {})",
+                    available.join(", ")
+                )
+            })
+    }
+
+    /// Inputs or outputs at a location, with their types. Structs are flattened, as that is how
+    /// naga represents inputs and outputs grouped together.
+    fn locations(
+        &self,
+        ty: Handle<Type>,
+        binding: Option<&Binding>,
+        locations: &mut Vec<(u32, Handle<Type>)>,
+    ) {
+        match (binding, &self.module.types[ty].inner) {
+            (Some(Binding::Location { location, .. }), _) => locations.push((*location, ty)),
+            (Some(Binding::BuiltIn(_)), _) => {}
+            (None, TypeInner::Struct { members, .. }) => {
+                for member in members {
+                    self.locations(member.ty, member.binding.as_ref(), locations);
+                }
+            }
+            (None, _) => {}
+        }
+    }
+
+    fn check_vertex_inputs(
+        &self,
+        index: usize,
+        buffers: &[wgpu::VertexBufferLayout],
+        mismatches: &mut Vec<String>,
+    ) {
+        let entry_point = &self.module.entry_points[index];
+        let mut inputs = vec![];
+        for argument in &entry_point.function.arguments {
+            self.locations(argument.ty, argument.binding.as_ref(), &mut inputs);
+        }
+
+        let mut attributes = vec![];
+        for (buffer_index, buffer) in buffers.iter().enumerate() {
+            for attribute in buffer.attributes {
+                if attribute.offset + attribute.format.size() > buffer.array_stride
+                    && buffer.array_stride != 0
+                {
+                    mismatches.push(format!(
+                        "Vertex buffer {buffer_index}: the attribute at location {} \
+                        (offset {}, {:?}) extends past the array stride of {}",
+                        attribute.shader_location,
+                        attribute.offset,
+                        attribute.format,
+                        buffer.array_stride
+                    ));
+                }
+                if let Some((other, _)) =
+                    attributes
+                        .iter()
+                        .find(|(_, other): &&(usize, &wgpu::VertexAttribute)| {
+                            other.shader_location == attribute.shader_location
+                        })
+                {
+                    mismatches.push(format!(
+                        "Vertex buffers {other} and {buffer_index} both provide location {}",
+                        attribute.shader_location
+                    ));
+                }
+                attributes.push((buffer_index, attribute));
+            }
+        }
+
+        for (location, ty) in inputs {
+            let expected = type_name(&self.module, ty);
+            let Some((buffer_index, attribute)) = attributes
+                .iter()
+                .find(|(_, attribute)| attribute.shader_location == location)
+            else {
+                mismatches.push(format!(
+                    "'{}' reads {expected} from location {location}, which no vertex buffer \
+                    provides",
+                    entry_point.name
+                ));
+                continue;
+            };
+            let provided = vertex_format_scalar(attribute.format);
+            if scalar(&self.module.types[ty].inner) != Some(provided) {
+                mismatches.push(format!(
+                    "'{}' reads {expected} from location {location}, but vertex buffer \
+                    {buffer_index} provides {:?}",
+                    entry_point.name, attribute.format
+                ));
+            }
+        }
+    }
+
+    fn check_fragment_outputs(
+        &self,
+        index: usize,
+        targets: &[Option<wgpu::ColorTargetState>],
+        mismatches: &mut Vec<String>,
+    ) {
+        let entry_point = &self.module.entry_points[index];
+        let mut outputs = vec![];
+        if let Some(result) = &entry_point.function.result {
+            self.locations(result.ty, result.binding.as_ref(), &mut outputs);
+        }
+
+        for &(location, ty) in &outputs {
+            let Some(Some(target)) = targets.get(location as usize) else {
+                mismatches.push(format!(
+                    "'{}' writes {} to location {location}, but the pipeline has no color \
+                    target there ({} color target(s))",
+                    entry_point.name,
+                    type_name(&self.module, ty),
+                    targets.len()
+                ));
+                continue;
+            };
+            let expected = match target.format.describe().sample_type {
+                wgpu::TextureSampleType::Sint => ScalarKind::Sint,
+                wgpu::TextureSampleType::Uint => ScalarKind::Uint,
+                _ => ScalarKind::Float,
+            };
+            if scalar(&self.module.types[ty].inner).map(|(kind, ..)| kind) != Some(expected) {
+                mismatches.push(format!(
+                    "'{}' writes {} to location {location}, which can't be stored in the \
+                    {:?} color target",
+                    entry_point.name,
+                    type_name(&self.module, ty),
+                    target.format
+                ));
+            }
+        }
+        for (location, target) in targets.iter().enumerate() {
+            let written = outputs
+                .iter()
+                .any(|(output, _)| *output as usize == location);
+            if let Some(target) = target {
+                if !written && target.write_mask != wgpu::ColorWrites::empty() {
+                    mismatches.push(format!(
+                        "Color target {location} ({:?}) is not written by '{}'",
+                        target.format, entry_point.name
+                    ));
+                }
+            }
+        }
+    }
+
+    fn check_bindings(
+        &self,
+        index: usize,
+        bind_group_layouts: &[&[wgpu::BindGroupLayoutEntry]],
+        mismatches: &mut Vec<String>,
+    ) {
+        let entry_point = &self.module.entry_points[index];
+        let stage = match entry_point.stage {
+            ShaderStage::Vertex => wgpu::ShaderStages::VERTEX,
+            ShaderStage::Fragment => wgpu::ShaderStages::FRAGMENT,
+            ShaderStage::Compute => wgpu::ShaderStages::COMPUTE,
+        };
+
+        for (handle, global) in self.module.global_variables.iter() {
+            let Some(binding) = &global.binding else {
+                continue;
+            };
+            let used = match &self.info {
+                Some(info) => !info.get_entry_point(index)[handle].is_empty(),
+                None => true,
+            };
+            if !used {
+                continue;
+            }
+
+            let name = match &global.name {
+                Some(name) => format!("'{name}' "),
+                None => String::new(),
+            };
+            let description = format!(
+                "'{}' uses {} {name}at group {}, binding {}",
+                entry_point.name,
+                type_name(&self.module, global.ty),
+                binding.group,
+                binding.binding
+            );
+            let Some(entries) = bind_group_layouts.get(binding.group as usize) else {
+                mismatches.push(format!(
+                    "{description}, but the pipeline layout has {} bind group(s)",
+                    bind_group_layouts.len()
+                ));
+                continue;
+            };
+            let Some(entry) = entries
+                .iter()
+                .find(|entry| entry.binding == binding.binding)
+            else {
+                mismatches.push(format!(
+                    "{description}, which is missing from the bind group layout"
+                ));
+                continue;
+            };
+
+            if !entry.visibility.contains(stage) {
+                mismatches.push(format!(
+                    "{description}, which is not visible to the {} stage ({:?})",
+                    stage_name(entry_point.stage),
+                    entry.visibility
+                ));
+            }
+            if let Err(reason) = self.check_binding_type(global.space, global.ty, &entry.ty) {
+                mismatches.push(format!("{description}, {reason}"));
+            }
+        }
+    }
+
+    fn check_binding_type(
+        &self,
+        space: AddressSpace,
+        ty: Handle<Type>,
+        binding_type: &wgpu::BindingType,
+    ) -> Result<(), String> {
+        let mismatch = || Err(format!("but the bind group layout has {binding_type:?}"));
+        match (space, &self.module.types[ty].inner, binding_type) {
+            (
+                AddressSpace::Uniform,
+                _,
+                wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    ..
+                },
+            ) => Ok(()),
+            (
+                AddressSpace::Storage { access },
+                _,
+                wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Storage { read_only },
+                    ..
+                },
+            ) => {
+                if *read_only && access.contains(StorageAccess::STORE) {
+                    Err(
+                        "which the shader writes to, but the bind group layout has it read only"
+                            .to_string(),
+                    )
+                } else {
+                    Ok(())
+                }
+            }
+            (
+                AddressSpace::Handle,
+                TypeInner::Image {
+                    dim,
+                    arrayed,
+                    class,
+                },
+                wgpu::BindingType::Texture {
+                    sample_type,
+                    view_dimension,
+                    multisampled,
+                },
+            ) => {
+                let matches = view_dimension_matches(*dim, *arrayed, *view_dimension)
+                    && match (class, sample_type) {
+                        (
+                            ImageClass::Sampled { kind, multi },
+                            wgpu::TextureSampleType::Float { .. },
+                        ) => *kind == ScalarKind::Float && multi == multisampled,
+                        (ImageClass::Sampled { kind, multi }, wgpu::TextureSampleType::Sint) => {
+                            *kind == ScalarKind::Sint && multi == multisampled
+                        }
+                        (ImageClass::Sampled { kind, multi }, wgpu::TextureSampleType::Uint) => {
+                            *kind == ScalarKind::Uint && multi == multisampled
+                        }
+                        (ImageClass::Depth { multi }, wgpu::TextureSampleType::Depth) => {
+                            multi == multisampled
+                        }
+                        _ => false,
+                    };
+                if matches {
+                    Ok(())
+                } else {
+                    mismatch()
+                }
+            }
+            (
+                AddressSpace::Handle,
+                TypeInner::Image {
+                    dim,
+                    arrayed,
+                    class: ImageClass::Storage { .. },
+                },
+                wgpu::BindingType::StorageTexture { view_dimension, .. },
+            ) if view_dimension_matches(*dim, *arrayed, *view_dimension) => Ok(()),
+            (
+                AddressSpace::Handle,
+                TypeInner::Sampler { comparison },
+                wgpu::BindingType::Sampler(sampler_type),
+            ) => {
+                if *comparison == (*sampler_type == wgpu::SamplerBindingType::Comparison) {
+                    Ok(())
+                } else {
+                    mismatch()
+                }
+            }
+            _ => mismatch(),
+        }
+    }
+}
+
+/// Scalar kind, width in bytes and component count of scalar and vector types.
+fn scalar(inner: &TypeInner) -> Option<(ScalarKind, u8, u8)> {
+    match *inner {
+        TypeInner::Scalar { kind, width } => Some((kind, width, 1)),
+        TypeInner::Vector { size, kind, width } => Some((kind, width, size as u8)),
+        _ => None,
+    }
+}
+
+fn view_dimension_matches(
+    dim: ImageDimension,
+    arrayed: bool,
+    view_dimension: wgpu::TextureViewDimension,
+) -> bool {
+    use wgpu::TextureViewDimension as View;
+    matches!(
+        (dim, arrayed, view_dimension),
+        (ImageDimension::D1, false, View::D1)
+            | (ImageDimension::D2, false, View::D2)
+            | (ImageDimension::D2, true, View::D2Array)
+            | (ImageDimension::D3, false, View::D3)
+            | (ImageDimension::Cube, false, View::Cube)
+            | (ImageDimension::Cube, true, View::CubeArray)
+    )
+}
+
+/// The scalar kind, width in bytes and component count a shader sees when reading a vertex
+/// attribute. Only 64 bit floats are read as anything but 32 bit scalars.
+fn vertex_format_scalar(format: wgpu::VertexFormat) -> (ScalarKind, u8, u8) {
+    use wgpu::VertexFormat as F;
+    match format {
+        F::Uint32 => (ScalarKind::Uint, 4, 1),
+        F::Uint8x2 | F::Uint16x2 | F::Uint32x2 => (ScalarKind::Uint, 4, 2),
+        F::Uint32x3 => (ScalarKind::Uint, 4, 3),
+        F::Uint8x4 | F::Uint16x4 | F::Uint32x4 => (ScalarKind::Uint, 4, 4),
+        F::Sint32 => (ScalarKind::Sint, 4, 1),
+        F::Sint8x2 | F::Sint16x2 | F::Sint32x2 => (ScalarKind::Sint, 4, 2),
+        F::Sint32x3 => (ScalarKind::Sint, 4, 3),
+        F::Sint8x4 | F::Sint16x4 | F::Sint32x4 => (ScalarKind::Sint, 4, 4),
+        F::Float32 => (ScalarKind::Float, 4, 1),
+        F::Unorm8x2 | F::Snorm8x2 | F::Unorm16x2 | F::Snorm16x2 | F::Float16x2 | F::Float32x2 => {
+            (ScalarKind::Float, 4, 2)
+        }
+        F::Float32x3 => (ScalarKind::Float, 4, 3),
+        F::Unorm8x4 | F::Snorm8x4 | F::Unorm16x4 | F::Snorm16x4 | F::Float16x4 | F::Float32x4 => {
+            (ScalarKind::Float, 4, 4)
+        }
+        F::Float64 => (ScalarKind::Float, 8, 1),
+        F::Float64x2 => (ScalarKind::Float, 8, 2),
+        F::Float64x3 => (ScalarKind::Float, 8, 3),
+        F::Float64x4 => (ScalarKind::Float, 8, 4),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SHADER: &str = "
+        struct VertexOutput {
+            @builtin(position) position: vec4<f32>,
+            @location(0) color: vec3<f32>,
+        }
+
+        @group(0) @binding(0) var<uniform> tint: vec4<f32>;
+        @group(0) @binding(1) var<storage, read_write> hits: array<u32>;
+
+        @vertex
+        fn main_vs(
+            @location(0) position: vec3<f32>,
+            @location(1) color: vec3<f32>,
+        ) -> VertexOutput {
+            return VertexOutput(vec4<f32>(position, 1.0), color);
+        }
+
+        @fragment
+        fn main_fs(in: VertexOutput) -> @location(0) vec4<f32> {
+            hits[0] = 1u;
+            return vec4<f32>(in.color, 1.0) * tint;
+        }
+    ";
+
+    const ATTRIBUTES: &[wgpu::VertexAttribute] =
+        &wgpu::vertex_attr_array![0 => Float32x3, 1 => Float32x3];
+
+    fn interface() -> ShaderInterface {
+        let module = naga::front::wgsl::parse_str(SHADER).unwrap();
+        let info = Validator::new(ValidationFlags::all(), Capabilities::all())
+            .validate(&module)
+            .unwrap();
+        ShaderInterface::from_module(module, info)
+    }
+
+    fn buffer(attributes: &[wgpu::VertexAttribute]) -> wgpu::VertexBufferLayout<'_> {
+        wgpu::VertexBufferLayout {
+            array_stride: 24,
+            step_mode: wgpu::VertexStepMode::Vertex,
+            attributes,
+        }
+    }
+
+    fn target() -> Option<wgpu::ColorTargetState> {
+        Some(wgpu::ColorTargetState {
+            format: wgpu::TextureFormat::Bgra8UnormSrgb,
+            blend: None,
+            write_mask: wgpu::ColorWrites::ALL,
+        })
+    }
+
+    fn buffer_entry(binding: u32, ty: wgpu::BufferBindingType) -> wgpu::BindGroupLayoutEntry {
+        wgpu::BindGroupLayoutEntry {
+            binding,
+            visibility: wgpu::ShaderStages::FRAGMENT,
+            ty: wgpu::BindingType::Buffer {
+                ty,
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+            count: None,
+        }
+    }
+
+    fn entries() -> [wgpu::BindGroupLayoutEntry; 2] {
+        [
+            buffer_entry(0, wgpu::BufferBindingType::Uniform),
+            buffer_entry(1, wgpu::BufferBindingType::Storage { read_only: false }),
+        ]
+    }
+
+    /// The mismatches of a pipeline with the given parts, the rest matching the shader.
+    fn mismatches(
+        buffers: &[wgpu::VertexBufferLayout],
+        targets: &[Option<wgpu::ColorTargetState>],
+        entries: &[wgpu::BindGroupLayoutEntry],
+    ) -> Vec<String> {
+        match interface().check_render_pipeline(
+            ("main_vs", buffers),
+            Some(("main_fs", targets)),
+            &[entries],
+        ) {
+            Ok(()) => vec![],
+            Err(ShaderInterfaceError::Mismatches(mismatches)) => mismatches,
+            Err(e) => panic!("{e}"),
+        }
+    }
+
+    #[test]
+    fn matching_pipeline_passes() {
+        assert_eq!(
+            mismatches(&[buffer(ATTRIBUTES)], &[target()], &entries()),
+            Vec::<String>::new()
+        );
+    }
+
+    #[test]
+    fn missing_location() {
+        assert_eq!(
+            mismatches(&[buffer(&ATTRIBUTES[..1])], &[target()], &entries()),
+            ["'main_vs' reads vec3<f32> from location 1, which no vertex buffer provides"]
+        );
+    }
+
+    #[test]
+    fn wrong_vertex_format() {
+        let attributes = wgpu::vertex_attr_array![0 => Float32x3, 1 => Float32x2];
+        let integers = wgpu::vertex_attr_array![0 => Float32x3, 1 => Uint32x3];
+        assert_eq!(
+            mismatches(&[buffer(&attributes)], &[target()], &entries()),
+            ["'main_vs' reads vec3<f32> from location 1, but vertex buffer 0 provides Float32x2"]
+        );
+        assert_eq!(
+            mismatches(&[buffer(&integers)], &[target()], &entries()),
+            ["'main_vs' reads vec3<f32> from location 1, but vertex buffer 0 provides Uint32x3"]
+        );
+    }
+
+    #[test]
+    fn vertex_format_widths() {
+        let doubles = wgpu::vertex_attr_array![0 => Float32x3, 1 => Float64x3];
+        let layout = wgpu::VertexBufferLayout {
+            array_stride: 36,
+            ..buffer(&doubles)
+        };
+        assert_eq!(
+            mismatches(&[layout], &[target()], &entries()),
+            ["'main_vs' reads vec3<f32> from location 1, but vertex buffer 0 provides Float64x3"]
+        );
+
+        let module = naga::front::wgsl::parse_str(
+            "
+            @vertex
+            fn main_vs(@location(0) position: vec3<f64>) -> @builtin(position) vec4<f32> {
+                return vec4<f32>(vec3<f32>(position), 1.0);
+            }
+            ",
+        )
+        .unwrap();
+        let info = Validator::new(ValidationFlags::all(), Capabilities::all())
+            .validate(&module)
+            .unwrap();
+        let interface = ShaderInterface::from_module(module, info);
+        let check = |format| {
+            let attributes = [wgpu::VertexAttribute {
+                format,
+                offset: 0,
+                shader_location: 0,
+            }];
+            interface
+                .check_render_pipeline(("main_vs", &[buffer(&attributes)]), None, &[])
+                .is_ok()
+        };
+        assert!(check(wgpu::VertexFormat::Float64x3));
+        assert!(!check(wgpu::VertexFormat::Float32x3));
+    }
+
+    #[test]
+    fn unwritten_color_target() {
+        assert_eq!(
+            mismatches(&[buffer(ATTRIBUTES)], &[target(), target()], &entries()),
+            ["Color target 1 (Bgra8UnormSrgb) is not written by 'main_fs'"]
+        );
+    }
+
+    #[test]
+    fn missing_color_target() {
+        assert_eq!(
+            mismatches(&[buffer(ATTRIBUTES)], &[None], &entries()),
+            [
+                "'main_fs' writes vec4<f32> to location 0, but the pipeline has no color target \
+                there (1 color target(s))"
+            ]
+        );
+    }
+
+    #[test]
+    fn binding_missing_from_layout() {
+        assert_eq!(
+            mismatches(&[buffer(ATTRIBUTES)], &[target()], &entries()[..1]),
+            [
+                "'main_fs' uses array<u32> 'hits' at group 0, binding 1, which is missing from \
+                the bind group layout"
+            ]
+        );
+    }
+
+    #[test]
+    fn wrong_visibility() {
+        let mut entries = entries();
+        entries[0].visibility = wgpu::ShaderStages::VERTEX;
+        assert_eq!(
+            mismatches(&[buffer(ATTRIBUTES)], &[target()], &entries),
+            [
+                "'main_fs' uses vec4<f32> 'tint' at group 0, binding 0, which is not visible to \
+                the fragment stage (VERTEX)"
+            ]
+        );
+    }
+
+    #[test]
+    fn read_only_storage_written_by_shader() {
+        let mut entries = entries();
+        entries[1] = buffer_entry(1, wgpu::BufferBindingType::Storage { read_only: true });
+        assert_eq!(
+            mismatches(&[buffer(ATTRIBUTES)], &[target()], &entries),
+            [
+                "'main_fs' uses array<u32> 'hits' at group 0, binding 1, which the shader writes \
+                to, but the bind group layout has it read only"
+            ]
+        );
+    }
+}
@@ -1,177 +1,591 @@
+use std::{
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use image::RgbaImage;
 use winit::{
     dpi::PhysicalSize,
     event::*,
     event_loop::{EventLoop, ControlFlow},
-    window::{Window, WindowBuilder},
+    window::Window,
 };
 
-use crate::RenderPassDresser;
+use crate::{
+    capture::{CaptureBuffer, CaptureError},
+    depth_buffer::{self, DepthBuffer},
+    frame_info::FrameTimer,
+    hot_reload::{self, ShaderReloadError, ShaderWatcher},
+    FrameDresser, FrameInfo, ViewportBuilder, ViewportError,
+};
+
+/// Texture format used for headless rendering. This is what most windowing systems hand us
+/// as an sRGB surface format, so dressers built for a window render identically offscreen.
+pub(crate) const HEADLESS_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Bgra8UnormSrgb;
+
+/// Color the frame is cleared to, unless configured otherwise.
+const DEFAULT_CLEAR_COLOR: wgpu::Color = wgpu::Color {
+    r: 0.1,
+    g: 0.2,
+    b: 0.3,
+    a: 1.0,
+};
+
+/// How often frame statistics are logged by default. They only show up when the `info` level is
+/// enabled for this crate, e.g. with `RUST_LOG=viewport=info`.
+const FRAME_STATS_INTERVAL: Duration = Duration::from_secs(5);
+
+pub(crate) enum RenderTarget {
+    Window {
+        surface: wgpu::Surface,
+        config: wgpu::SurfaceConfiguration,
+        window: Window,
+    },
+    Offscreen {
+        texture: wgpu::Texture,
+    },
+}
 
 pub struct Viewport {
-    surface: wgpu::Surface,
+    target: RenderTarget,
+    adapter: wgpu::Adapter,
     device: wgpu::Device,
     queue: wgpu::Queue,
-    config: wgpu::SurfaceConfiguration,
     size: winit::dpi::PhysicalSize<u32>,
     surface_format: wgpu::TextureFormat,
-    window: Window,
+    frame_timer: FrameTimer,
+    depth_buffer: Option<DepthBuffer>,
+    // Only present when rendering with more than one sample, resolved into the frame
+    multisample_view: Option<wgpu::TextureView>,
+    sample_count: u32,
+    color_load_op: wgpu::LoadOp<wgpu::Color>,
+    depth_clear_value: f32,
+    stencil_clear_value: u32,
+    shader_watcher: ShaderWatcher,
 }
 
 impl Viewport {
-    // Creating some of the wgpu types requires async code
-    pub async fn new(width: u32, height: u32, event_loop: &EventLoop<()>) -> Self {
-        let window = WindowBuilder::new()
-            .with_inner_size(PhysicalSize::new(width, height))
-            .build(event_loop)
-            .unwrap();
-
-        let size = window.inner_size();
-
-        // The instance is a handle to our GPU
-        // Backends::all => Vulkan + Metal + DX12 + Browser WebGPU
-        let instance = wgpu::Instance::new(wgpu::InstanceDescriptor {
-            backends: wgpu::Backends::all(),
-            dx12_shader_compiler: Default::default(),
-        });
+    /// Create a window of the given size and a viewport presenting to it, see
+    /// [`ViewportBuilder`] for more options.
+    pub async fn new(
+        width: u32,
+        height: u32,
+        event_loop: &EventLoop<()>,
+    ) -> Result<Self, ViewportError> {
+        ViewportBuilder::new(width, height).build(event_loop).await
+    }
 
-        // # Safety
-        //
-        // The surface needs to live as long as the window that created it.
-        // State owns the window so this should be safe.
-        let surface = unsafe { instance.create_surface(&window) }.expect("Error creating surface!");
-
-        let adapter = instance
-            .request_adapter(&wgpu::RequestAdapterOptions {
-                power_preference: wgpu::PowerPreference::default(),
-                compatible_surface: Some(&surface),
-                force_fallback_adapter: false,
-            })
-            .await
-            .expect("Requested adapter was none 'None'");
-
-        let (device, queue) = adapter
-            .request_device(
-                &wgpu::DeviceDescriptor {
-                    features: wgpu::Features::empty() | wgpu::Features::SPIRV_SHADER_PASSTHROUGH,
-                    limits: wgpu::Limits::default(),
-                    label: None,
-                },
-                None, // Trace path
-            )
+    /// Create a viewport without a window, which renders into an owned texture instead of a
+    /// surface. Set `force_fallback_adapter` to request a software adapter, e.g. on machines
+    /// without a GPU.
+    pub async fn new_headless(
+        width: u32,
+        height: u32,
+        force_fallback_adapter: bool,
+    ) -> Result<Self, ViewportError> {
+        ViewportBuilder::new(width, height)
+            .force_fallback_adapter(force_fallback_adapter)
+            .build_headless()
             .await
-            .expect("Error requesting device!");
-
-        let surface_caps = surface.get_capabilities(&adapter);
-        // Shader code in this tutorial assumes an sRGB surface texture. Using a different
-        // one will result all the colors coming out darker. If you want to support non
-        // sRGB surfaces, you'll need to account for that when drawing to the frame.
-        let surface_format = surface_caps
-            .formats
-            .iter()
-            .copied()
-            .filter(|f| f.describe().srgb)
-            .next()
-            .unwrap_or(surface_caps.formats[0]);
-        let config = wgpu::SurfaceConfiguration {
-            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
-            format: surface_format,
-            width: size.width,
-            height: size.height,
-            present_mode: surface_caps.present_modes[0],
-            alpha_mode: surface_caps.alpha_modes[0],
-            view_formats: vec![],
-        };
-        surface.configure(&device, &config);
+    }
 
+    pub(crate) fn from_target(
+        target: RenderTarget,
+        adapter: wgpu::Adapter,
+        device: wgpu::Device,
+        queue: wgpu::Queue,
+        size: PhysicalSize<u32>,
+        surface_format: wgpu::TextureFormat,
+    ) -> Self {
         Self {
-            window,
-            surface,
+            target,
+            adapter,
             device,
             queue,
-            config,
             size,
             surface_format,
+            frame_timer: FrameTimer::new(Some(FRAME_STATS_INTERVAL)),
+            depth_buffer: None,
+            multisample_view: None,
+            sample_count: 1,
+            color_load_op: wgpu::LoadOp::Clear(DEFAULT_CLEAR_COLOR),
+            depth_clear_value: 1.0,
+            stencil_clear_value: 0,
+            shader_watcher: ShaderWatcher::default(),
         }
     }
 
-    pub fn run<Dresser>(mut viewport: Self, event_loop: EventLoop<()>, render_pass_dresser: Dresser)
+    fn create_multisample_view(
+        device: &wgpu::Device,
+        size: PhysicalSize<u32>,
+        format: wgpu::TextureFormat,
+        sample_count: u32,
+    ) -> wgpu::TextureView {
+        device
+            .create_texture(&wgpu::TextureDescriptor {
+                label: Some("Multisample Texture"),
+                size: wgpu::Extent3d {
+                    width: size.width,
+                    height: size.height,
+                    depth_or_array_layers: 1,
+                },
+                mip_level_count: 1,
+                sample_count,
+                dimension: wgpu::TextureDimension::D2,
+                format,
+                usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+                view_formats: &[],
+            })
+            .create_view(&wgpu::TextureViewDescriptor::default())
+    }
+
+    pub(crate) fn create_offscreen_texture(
+        device: &wgpu::Device,
+        size: PhysicalSize<u32>,
+        format: wgpu::TextureFormat,
+    ) -> wgpu::Texture {
+        device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Offscreen Texture"),
+            size: wgpu::Extent3d {
+                width: size.width,
+                height: size.height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            // COPY_SRC allows reading the rendered frame back to the CPU
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+            view_formats: &[],
+        })
+    }
+
+    /// Run the event loop, rendering a frame with the dresser whenever the window requests
+    /// a redraw. Errors are printed, except running out of memory which exits the event loop.
+    pub fn run<Dresser>(viewport: Self, event_loop: EventLoop<()>, dresser: Dresser)
     where
-        Dresser: RenderPassDresser + 'static,
+        Dresser: FrameDresser + 'static,
+    {
+        Self::run_with_error_handler(
+            viewport,
+            event_loop,
+            dresser,
+            |error, control_flow| match error {
+                // The system is out of memory, we should probably quit
+                ViewportError::SurfaceAcquire(wgpu::SurfaceError::OutOfMemory) => {
+                    *control_flow = ControlFlow::Exit
+                }
+                // All other errors (Outdated, Timeout) should be resolved by the next frame
+                e => eprintln!("{e}"),
+            },
+        );
+    }
+
+    /// Like [`Viewport::run`], but errors occurring while rendering are passed to `on_error`,
+    /// which can decide to exit the event loop. A lost surface is reconfigured by the viewport
+    /// itself.
+    pub fn run_with_error_handler<Dresser, ErrorHandler>(
+        mut viewport: Self,
+        event_loop: EventLoop<()>,
+        mut dresser: Dresser,
+        mut on_error: ErrorHandler,
+    ) where
+        Dresser: FrameDresser + 'static,
+        ErrorHandler: FnMut(ViewportError, &mut ControlFlow) + 'static,
     {
+        let viewport_window_id = viewport
+            .window()
+            .expect("Only windowed viewports can be run in an event loop!")
+            .id();
         event_loop.run(move |event, _, control_flow| match event {
             Event::WindowEvent {
                 ref event,
                 window_id,
-            } if window_id == viewport.window().id() => match event {
-                WindowEvent::Resized(physical_size) => {
-                    viewport.resize(*physical_size);
-                }
-                WindowEvent::ScaleFactorChanged { new_inner_size, .. } => {
-                    // new_inner_size is &&mut so we have to dereference it twice
-                    viewport.resize(**new_inner_size);
+            } if window_id == viewport_window_id
+                && !viewport.input(event, &mut dresser) =>
+            {
+                match event {
+                    WindowEvent::Resized(physical_size) => {
+                        viewport.resize_with(*physical_size, &mut dresser);
+                    }
+                    WindowEvent::ScaleFactorChanged { new_inner_size, .. } => {
+                        // new_inner_size is &&mut so we have to dereference it twice
+                        viewport.resize_with(**new_inner_size, &mut dresser);
+                    }
+                    WindowEvent::CloseRequested
+                    | WindowEvent::KeyboardInput {
+                        input:
+                            KeyboardInput {
+                                state: ElementState::Pressed,
+                                virtual_keycode: Some(VirtualKeyCode::Escape),
+                                ..
+                            },
+                        ..
+                    } => *control_flow = ControlFlow::Exit,
+                    _ => {}
                 }
-                WindowEvent::CloseRequested
-                | WindowEvent::KeyboardInput {
-                    input:
-                        KeyboardInput {
-                            state: ElementState::Pressed,
-                            virtual_keycode: Some(VirtualKeyCode::Escape),
-                            ..
-                        },
-                    ..
-                } => *control_flow = ControlFlow::Exit,
-                _ => {}
-            },
-            Event::RedrawRequested(window_id) if window_id == viewport.window().id() => {
-                viewport.update();
-                match viewport.render(&render_pass_dresser) {
+            }
+            Event::RedrawRequested(window_id) if window_id == viewport_window_id => {
+                viewport.update(&mut dresser);
+                match viewport.render(&mut dresser) {
                     Ok(_) => {}
                     // Reconfigure the surface if lost
-                    Err(wgpu::SurfaceError::Lost) => viewport.resize(viewport.size()),
-                    // The system is out of memory, we should probably quit
-                    Err(wgpu::SurfaceError::OutOfMemory) => *control_flow = ControlFlow::Exit,
-                    // All other errors (Outdated, Timeout) should be resolved by the next frame
-                    Err(e) => eprintln!("{:?}", e),
+                    Err(ViewportError::SurfaceAcquire(wgpu::SurfaceError::Lost)) => {
+                        viewport.resize(viewport.size())
+                    }
+                    Err(e) => on_error(e, control_flow),
                 }
             }
             Event::MainEventsCleared => {
                 // RedrawRequested will only trigger once, unless we manually
                 // request it.
-                viewport.window().request_redraw();
+                if let Some(window) = viewport.window() {
+                    window.request_redraw();
+                }
             }
             _ => {}
         });
     }
 
-    pub fn window(&self) -> &Window {
-        &self.window
+    /// Drive the dresser for the given number of frames without an event loop. All submitted
+    /// work has finished by the time this returns.
+    pub fn run_headless<Dresser: FrameDresser>(
+        &mut self,
+        dresser: &mut Dresser,
+        frames: u32,
+    ) -> Result<(), ViewportError> {
+        for _ in 0..frames {
+            self.update(dresser);
+            self.render(dresser)?;
+        }
+        self.device.poll(wgpu::Maintain::Wait);
+
+        Ok(())
+    }
+
+    /// The window this viewport presents to, `None` for headless viewports.
+    pub fn window(&self) -> Option<&Window> {
+        match &self.target {
+            RenderTarget::Window { window, .. } => Some(window),
+            RenderTarget::Offscreen { .. } => None,
+        }
+    }
+
+    /// The texture a headless viewport renders into, `None` for windowed viewports.
+    pub fn offscreen_texture(&self) -> Option<&wgpu::Texture> {
+        match &self.target {
+            RenderTarget::Window { .. } => None,
+            RenderTarget::Offscreen { texture } => Some(texture),
+        }
     }
 
     pub fn resize(&mut self, new_size: winit::dpi::PhysicalSize<u32>) {
         if new_size.width > 0 && new_size.height > 0 {
             self.size = new_size;
-            self.config.width = new_size.width;
-            self.config.height = new_size.height;
-            self.surface.configure(&self.device, &self.config);
+            match &mut self.target {
+                RenderTarget::Window { surface, config, .. } => {
+                    config.width = new_size.width;
+                    config.height = new_size.height;
+                    surface.configure(&self.device, config);
+                }
+                RenderTarget::Offscreen { texture } => {
+                    *texture =
+                        Self::create_offscreen_texture(&self.device, new_size, self.surface_format);
+                }
+            }
+            if let Some(depth_buffer) = &mut self.depth_buffer {
+                *depth_buffer = DepthBuffer::new(
+                    &self.device,
+                    depth_buffer.format(),
+                    new_size,
+                    depth_buffer.sample_count(),
+                );
+            }
+            if self.multisample_view.is_some() {
+                self.multisample_view = Some(Self::create_multisample_view(
+                    &self.device,
+                    new_size,
+                    self.surface_format,
+                    self.sample_count,
+                ));
+            }
+        }
+    }
+
+    /// Set the number of samples per pixel. With more than one sample, frames are rendered into
+    /// a multisampled texture which is resolved into the surface. 1 and 4 are supported on
+    /// all adapters, other sample counts fail with [`ViewportError::UnsupportedSampleCount`]
+    /// unless the adapter supports them.
+    ///
+    /// Pipelines need a matching multisample state, see [`Viewport::multisample_state`].
+    pub fn set_sample_count(&mut self, sample_count: u32) -> Result<(), ViewportError> {
+        self.check_targets(sample_count, self.depth_format())?;
+        self.sample_count = sample_count;
+        self.multisample_view = (sample_count > 1).then(|| {
+            Self::create_multisample_view(&self.device, self.size, self.surface_format, sample_count)
+        });
+        if let Some(depth_buffer) = &mut self.depth_buffer {
+            *depth_buffer =
+                DepthBuffer::new(&self.device, depth_buffer.format(), self.size, sample_count);
+        }
+        Ok(())
+    }
+
+    pub fn sample_count(&self) -> u32 {
+        self.sample_count
+    }
+
+    /// Multisample state for pipelines rendering to this viewport.
+    pub fn multisample_state(&self) -> wgpu::MultisampleState {
+        wgpu::MultisampleState {
+            count: self.sample_count,
+            mask: !0,
+            alpha_to_coverage_enabled: false,
+        }
+    }
+
+    /// Set the format of the depth buffer attached to every frame, `None` removes it. Formats
+    /// that aren't depth or stencil formats, or that need features the device was created
+    /// without, are rejected.
+    ///
+    /// Pipelines need a matching depth stencil state, see [`Viewport::depth_stencil_state`].
+    pub fn set_depth_format(
+        &mut self,
+        format: Option<wgpu::TextureFormat>,
+    ) -> Result<(), ViewportError> {
+        self.check_targets(self.sample_count, format)?;
+        self.depth_buffer = format
+            .map(|format| DepthBuffer::new(&self.device, format, self.size, self.sample_count));
+        Ok(())
+    }
+
+    pub fn depth_format(&self) -> Option<wgpu::TextureFormat> {
+        self.depth_buffer.as_ref().map(DepthBuffer::format)
+    }
+
+    /// Check that the device can render to the frame and depth buffer with the given sample
+    /// count and depth format, before wgpu's validation panics on them.
+    fn check_targets(
+        &self,
+        sample_count: u32,
+        depth_format: Option<wgpu::TextureFormat>,
+    ) -> Result<(), ViewportError> {
+        // Multisampled frames are resolved into the surface or offscreen texture
+        let color_flags = self.format_features(self.surface_format).flags;
+        if !color_flags.sample_count_supported(sample_count)
+            || sample_count > 1
+                && !color_flags.contains(wgpu::TextureFormatFeatureFlags::MULTISAMPLE_RESOLVE)
+        {
+            return Err(ViewportError::UnsupportedSampleCount {
+                format: self.surface_format,
+                sample_count,
+            });
+        }
+
+        if let Some(format) = depth_format {
+            if !depth_buffer::is_depth_stencil(format) {
+                return Err(ViewportError::NotADepthFormat(format));
+            }
+            let missing = format.describe().required_features - self.device.features();
+            if !missing.is_empty() {
+                return Err(ViewportError::UnsupportedFormat { format, missing });
+            }
+            if !self.format_features(format).flags.sample_count_supported(sample_count) {
+                return Err(ViewportError::UnsupportedSampleCount {
+                    format,
+                    sample_count,
+                });
+            }
         }
+
+        Ok(())
+    }
+
+    /// Features of `format` the way wgpu validates textures: only the ones guaranteed by
+    /// WebGPU, unless the device is downlevel or allows adapter specific format features.
+    fn format_features(&self, format: wgpu::TextureFormat) -> wgpu::TextureFormatFeatures {
+        let adapter_specific = self
+            .device
+            .features()
+            .contains(wgpu::Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES);
+        if adapter_specific || !self.adapter.get_downlevel_capabilities().is_webgpu_compliant() {
+            self.adapter.get_texture_format_features(format)
+        } else {
+            format.describe().guaranteed_format_features
+        }
+    }
+
+    /// Clear the color target to the given color at the start of every frame.
+    pub fn set_clear_color(&mut self, color: wgpu::Color) {
+        self.color_load_op = wgpu::LoadOp::Clear(color);
+    }
+
+    /// Set what happens to the color target at the start of every frame, unless a dresser
+    /// overrides it. `LoadOp::Load` keeps the contents of the previous frame.
+    pub fn set_color_load_op(&mut self, load_op: wgpu::LoadOp<wgpu::Color>) {
+        self.color_load_op = load_op;
+    }
+
+    pub fn color_load_op(&self) -> wgpu::LoadOp<wgpu::Color> {
+        self.color_load_op
+    }
+
+    /// Value the depth buffer is cleared to at the start of every frame, 1.0 by default.
+    pub fn set_depth_clear_value(&mut self, depth_clear_value: f32) {
+        self.depth_clear_value = depth_clear_value;
+    }
+
+    /// Value the stencil buffer is cleared to at the start of every frame, 0 by default.
+    pub fn set_stencil_clear_value(&mut self, stencil_clear_value: u32) {
+        self.stencil_clear_value = stencil_clear_value;
+    }
+
+    /// Depth stencil state for pipelines rendering to this viewport, which keeps the nearest
+    /// fragments. `None` if the viewport has no depth buffer.
+    pub fn depth_stencil_state(&self) -> Option<wgpu::DepthStencilState> {
+        self.depth_format().map(|format| wgpu::DepthStencilState {
+            format,
+            depth_write_enabled: true,
+            depth_compare: wgpu::CompareFunction::Less,
+            stencil: wgpu::StencilState::default(),
+            bias: wgpu::DepthBiasState::default(),
+        })
+    }
+
+    /// Resize the viewport and let the dresser know about it.
+    fn resize_with<Dresser: FrameDresser>(
+        &mut self,
+        new_size: winit::dpi::PhysicalSize<u32>,
+        dresser: &mut Dresser,
+    ) {
+        if new_size.width > 0 && new_size.height > 0 {
+            self.resize(new_size);
+            dresser.resize(&self.device, new_size);
+        }
+    }
+
+    pub fn input<Dresser: FrameDresser>(
+        &mut self,
+        event: &WindowEvent,
+        dresser: &mut Dresser,
+    ) -> bool {
+        dresser.input(event)
+    }
+
+    pub fn update<Dresser: FrameDresser>(&mut self, dresser: &mut Dresser) {
+        self.reload_shaders(dresser);
+        let dt = self.frame_timer.tick();
+        dresser.update(dt);
+    }
+
+    /// Watch a SPIR-V module on disk, e.g. `target/triangle.spv`. Whenever it changes, it is
+    /// loaded again and passed to [`DresserHooks::reload_pipeline`](crate::DresserHooks::reload_pipeline) under the given name. The
+    /// dresser keeps its current pipeline if the new module or pipeline fails validation, or the
+    /// pipeline doesn't match the interface of the new module.
+    pub fn watch_shader(&mut self, shader: impl Into<String>, path: impl Into<PathBuf>) {
+        self.shader_watcher.watch(shader.into(), path.into());
+    }
+
+    fn reload_shaders<Dresser: FrameDresser>(&mut self, dresser: &mut Dresser) {
+        for (shader, path) in self.shader_watcher.poll() {
+            match self.reload_shader(dresser, &shader, &path) {
+                Ok(()) => log::info!("Reloaded shader '{shader}' from {}", path.display()),
+                Err(e) => log::error!(
+                    "Error reloading shader '{shader}' from {}, keeping the previous pipeline: {e}",
+                    path.display()
+                ),
+            }
+        }
+    }
+
+    fn reload_shader<Dresser: FrameDresser>(
+        &self,
+        dresser: &mut Dresser,
+        shader: &str,
+        path: &Path,
+    ) -> Result<(), ShaderReloadError> {
+        let spirv = hot_reload::read_spirv(path)?;
+        let interface = hot_reload::validate_spirv(&spirv)?;
+
+        // Validation errors would otherwise end up in the uncaptured error handler, which panics
+        self.device.push_error_scope(wgpu::ErrorFilter::Validation);
+        let module = unsafe {
+            self.device
+                .create_shader_module_spirv(&wgpu::ShaderModuleDescriptorSpirV {
+                    label: Some(shader),
+                    source: wgpu::util::make_spirv_raw(&spirv),
+                })
+        };
+        let pipeline = dresser.reload_pipeline(self, shader, &module, &interface);
+        if let Some(e) = pollster::block_on(self.device.pop_error_scope()) {
+            return Err(ShaderReloadError::Validation(Box::new(e)));
+        }
+
+        let pipeline = pipeline
+            .ok_or(ShaderReloadError::NotReloaded)?
+            .map_err(ShaderReloadError::Interface)?;
+        dresser.replace_pipeline(shader, pipeline);
+        Ok(())
     }
 
-    pub fn input(&mut self, _event: &WindowEvent) -> bool {
-        false
+    /// Timing information of the current frame, as passed to [`DresserHooks::prepare`](crate::DresserHooks::prepare).
+    pub fn frame_info(&self) -> FrameInfo {
+        self.frame_timer.frame_info(self.size)
     }
 
-    pub fn update(&mut self) {
-        // TODO
+    /// Set how often frame statistics are logged, `None` disables them.
+    pub fn set_frame_stats_interval(&mut self, interval: Option<Duration>) {
+        self.frame_timer.set_log_interval(interval);
     }
 
-    pub fn render<Dresser: RenderPassDresser>(
+    pub fn render<Dresser: FrameDresser>(
         &self,
-        render_pass_dresser: &Dresser,
-    ) -> Result<(), wgpu::SurfaceError> {
+        dresser: &mut Dresser,
+    ) -> Result<(), ViewportError> {
+        dresser.prepare(&self.device, &self.queue, &self.frame_info());
         let mut renderer = Renderer::new(self)?;
-        render_pass_dresser.dress(renderer.render_pass());
+        dresser.dress_frame(&mut renderer);
+        renderer.render();
+
+        Ok(())
+    }
+
+    /// Render a single frame with the given dresser and read it back as an RGBA image.
+    ///
+    /// Windowed viewports render the captured frame into a separate texture, so the
+    /// surface does not need to support being copied from.
+    pub fn capture<Dresser: FrameDresser>(
+        &self,
+        dresser: &mut Dresser,
+    ) -> Result<RgbaImage, CaptureError> {
+        dresser.prepare(&self.device, &self.queue, &self.frame_info());
+        let capture_buffer = CaptureBuffer::new(&self.device, self.surface_format, self.size)?;
+
+        let capture_texture;
+        let (mut renderer, texture) = match &self.target {
+            RenderTarget::Window { .. } => {
+                capture_texture =
+                    Self::create_offscreen_texture(&self.device, self.size, self.surface_format);
+                (Renderer::for_texture(self, &capture_texture), &capture_texture)
+            }
+            RenderTarget::Offscreen { texture } => (Renderer::new(self)?, texture),
+        };
+        dresser.dress_frame(&mut renderer);
+        capture_buffer.copy_from(&mut renderer.encoder, texture);
         renderer.render();
 
+        capture_buffer.read(&self.device)
+    }
+
+    /// Render a single frame with the given dresser and save it as a PNG.
+    pub fn save_png<Dresser: FrameDresser, P: AsRef<Path>>(
+        &self,
+        dresser: &mut Dresser,
+        path: P,
+    ) -> Result<(), CaptureError> {
+        self.capture(dresser)?.save(path)?;
+
         Ok(())
     }
 
@@ -192,54 +606,104 @@ impl Viewport {
     }
 }
 
+/// A frame being rendered: the command encoder recording it and the view it ends up in.
 pub struct Renderer<'a> {
     encoder: wgpu::CommandEncoder,
-    output: wgpu::SurfaceTexture,
+    // `None` when rendering offscreen, there is nothing to present in that case
+    output: Option<wgpu::SurfaceTexture>,
     view: wgpu::TextureView,
-    queue: &'a wgpu::Queue,
+    viewport: &'a Viewport,
 }
 
 impl<'a> Renderer<'a> {
     fn new(viewport: &'a Viewport) -> Result<Self, wgpu::SurfaceError> {
-        let output = viewport.surface.get_current_texture()?;
-        let view = output
-            .texture
-            .create_view(&wgpu::TextureViewDescriptor::default());
+        let (output, view) = match &viewport.target {
+            RenderTarget::Window { surface, .. } => {
+                let output = surface.get_current_texture()?;
+                let view = output
+                    .texture
+                    .create_view(&wgpu::TextureViewDescriptor::default());
+                (Some(output), view)
+            }
+            RenderTarget::Offscreen { texture } => {
+                (None, texture.create_view(&wgpu::TextureViewDescriptor::default()))
+            }
+        };
+        Ok(Self::with_view(viewport, output, view))
+    }
+
+    /// Render into the given texture instead of the viewport's target.
+    fn for_texture(viewport: &'a Viewport, texture: &wgpu::Texture) -> Self {
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        Self::with_view(viewport, None, view)
+    }
+
+    fn with_view(
+        viewport: &'a Viewport,
+        output: Option<wgpu::SurfaceTexture>,
+        view: wgpu::TextureView,
+    ) -> Self {
         let encoder = viewport
             .device
             .create_command_encoder(&wgpu::CommandEncoderDescriptor {
                 label: Some("Render Encoder"),
             });
-        Ok(Self {
+        Self {
             encoder,
             output,
             view,
-            queue: &viewport.queue,
-        })
+            viewport,
+        }
+    }
+
+    pub fn viewport(&self) -> &Viewport {
+        self.viewport
+    }
+
+    /// The encoder the frame is recorded into, e.g. to add compute or offscreen passes.
+    pub fn encoder(&mut self) -> &mut wgpu::CommandEncoder {
+        &mut self.encoder
     }
 
-    pub fn render_pass(&mut self) -> wgpu::RenderPass {
+    /// View of the surface texture (or offscreen texture) this frame is presented from.
+    ///
+    /// When multisampling, render passes should target the viewport's multisampled texture and
+    /// resolve into this view instead, which is what [`Renderer::render_pass`] does.
+    pub fn view(&self) -> &wgpu::TextureView {
+        &self.view
+    }
+
+    /// Begin a render pass into the frame, with the viewport's multisample and depth targets
+    /// attached.
+    pub fn render_pass(&mut self, load_op: wgpu::LoadOp<wgpu::Color>) -> wgpu::RenderPass {
+        // When multisampling, render into the multisampled texture and resolve into the frame
+        let (view, resolve_target) = match &self.viewport.multisample_view {
+            Some(multisample_view) => (multisample_view, Some(&self.view)),
+            None => (&self.view, None),
+        };
         self.encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
             label: Some("Render Pass"),
             color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                view: &self.view,
-                resolve_target: None,
+                view,
+                resolve_target,
                 ops: wgpu::Operations {
-                    load: wgpu::LoadOp::Clear(wgpu::Color {
-                        r: 0.1,
-                        g: 0.2,
-                        b: 0.3,
-                        a: 1.0,
-                    }),
+                    load: load_op,
                     store: true,
                 },
             })],
-            depth_stencil_attachment: None,
+            depth_stencil_attachment: self.viewport.depth_buffer.as_ref().map(|depth_buffer| {
+                depth_buffer.attachment(
+                    self.viewport.depth_clear_value,
+                    self.viewport.stencil_clear_value,
+                )
+            }),
         })
     }
 
-    pub fn render(self) {
-        self.queue.submit(std::iter::once(self.encoder.finish()));
-        self.output.present();
+    pub(crate) fn render(self) {
+        self.viewport.queue.submit(std::iter::once(self.encoder.finish()));
+        if let Some(output) = self.output {
+            output.present();
+        }
     }
 }